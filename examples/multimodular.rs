@@ -0,0 +1,41 @@
+/*
+ * Times Lemke's algorithm on the tracing LCP of seeded random games with
+ * the default  Tableau  and with  ModularTableau,
+ *
+ *     cargo run --release --features multimodular --example multimodular
+ */
+extern crate gametheory;
+extern crate num;
+
+use num::bigint::BigInt;
+use num::rational::BigRational;
+
+use std::time::{Duration,Instant};
+
+use gametheory::game::{GameGenerator,tracing_lcp};
+
+fn main() {
+
+    println!("{:>6} {:>8} {:>12} {:>12} {:>8}", "size", "pivots", "bigint", "modular", "ratio");
+    for &size in &[15, 25, 35, 45, 60] {
+        let (mut exact_time, mut modular_time, mut pivots) = (Duration::new(0, 0), Duration::new(0, 0), 0);
+        for seed in 0..5 {
+            let game = GameGenerator::new(seed).uniform(size, size, -10, 10);
+            let p = vec![BigRational::new(BigInt::from(1), BigInt::from(size)); size];
+
+            let mut exact = tracing_lcp(&game, &p, &p);
+            let start = Instant::now();
+            let z = exact.solve(0).unwrap();
+            exact_time += start.elapsed();
+
+            let mut modular = tracing_lcp(&game, &p, &p);
+            modular.use_multimodular();
+            let start = Instant::now();
+            assert_eq!(z, modular.solve(0).unwrap());
+            modular_time += start.elapsed();
+            pivots += exact.pivot_count;
+        }
+        println!("{:>6} {:>8} {:>12.1?} {:>12.1?} {:>8.2}", size, pivots, exact_time, modular_time,
+            modular_time.as_secs_f64() / exact_time.as_secs_f64());
+    }
+}
@@ -0,0 +1,157 @@
+use num::rational::BigRational;
+use num::traits::{Zero,One,Signed};
+
+use std::ops::{Add,Mul,Sub};
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+
+/*
+ * Two-player game in normal form:  A  and  B  are the  m x n  payoff
+ * matrices (row-major) of the row player and the column player.
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct Bimatrix {
+    pub m: usize,
+    pub n: usize,
+    pub a: Vec<BigRational>,
+    pub b: Vec<BigRational>,
+}
+
+/*
+ * mixed strategies  x  of the row player and  y  of the column player,
+ * with their expected payoffs  x'Ay  and  x'By
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct Equilibrium {
+    pub x: Vec<BigRational>,
+    pub y: Vec<BigRational>,
+    pub payoff1: BigRational,
+    pub payoff2: BigRational,
+}
+
+impl Bimatrix {
+
+    pub fn new(m: usize, n: usize, a: Vec<BigRational>, b: Vec<BigRational>) -> Bimatrix {
+        if m == 0 || n == 0 {
+            panic!("Each player needs at least one strategy but the game is {}x{}", m, n);
+        }
+        if a.len() != m * n || b.len() != m * n {
+            panic!("A and B must be {}x{} but have {} and {} entries", m, n, a.len(), b.len());
+        }
+        Bimatrix { m, n, a, b }
+    }
+
+    pub fn a(&self, i: usize, j: usize) -> &BigRational {
+        &self.a[i*self.n + j]
+    }
+
+    pub fn b(&self, i: usize, j: usize) -> &BigRational {
+        &self.b[i*self.n + j]
+    }
+
+    // expected payoffs  x'Ay  and  x'By
+    pub fn payoffs(&self, x: &[BigRational], y: &[BigRational]) -> (BigRational, BigRational) {
+        (dot(x, &self.row_payoffs(y)), dot(y, &self.col_payoffs(x)))
+    }
+
+    // payoffs  Ay  of the row player's pure strategies against  y
+    pub fn row_payoffs(&self, y: &[BigRational]) -> Vec<BigRational> {
+        (0..self.m).map(|i| (0..self.n).fold(BigRational::zero(), |acc, j| acc.add(self.a(i, j).clone().mul(&y[j])))).collect()
+    }
+
+    // payoffs  x'B  of the column player's pure strategies against  x
+    pub fn col_payoffs(&self, x: &[BigRational]) -> Vec<BigRational> {
+        (0..self.n).map(|j| (0..self.m).fold(BigRational::zero(), |acc, i| acc.add(self.b(i, j).clone().mul(&x[i])))).collect()
+    }
+
+    // the game with the same equilibria where every payoff is at least one
+    pub fn positive(&self) -> Bimatrix {
+        Bimatrix {
+            m: self.m,
+            n: self.n,
+            a: shift_to_positive(&self.a),
+            b: shift_to_positive(&self.b),
+        }
+    }
+
+    // the costs of both players, see  costs
+    pub fn costs(&self) -> Bimatrix {
+        Bimatrix {
+            m: self.m,
+            n: self.n,
+            a: costs(&self.a),
+            b: costs(&self.b),
+        }
+    }
+
+    /*
+     * whether  x  and  y  are mixed strategies that are best responses
+     * to each other, i.e. only pure best responses have positive probability
+     */
+    pub fn is_equilibrium(&self, x: &[BigRational], y: &[BigRational]) -> bool {
+        if !is_mixed_strategy(x, self.m) || !is_mixed_strategy(y, self.n) {
+            return false;
+        }
+        is_supported_by_best_responses(x, &self.row_payoffs(y)) && is_supported_by_best_responses(y, &self.col_payoffs(x))
+    }
+
+    pub fn equilibrium(&self, x: Vec<BigRational>, y: Vec<BigRational>) -> Equilibrium {
+        let (payoff1, payoff2) = self.payoffs(&x, &y);
+        Equilibrium { x, y, payoff1, payoff2 }
+    }
+}
+
+fn dot(x: &[BigRational], y: &[BigRational]) -> BigRational {
+    x.iter().zip(y.iter()).fold(BigRational::zero(), |acc, (x_i, y_i)| acc.add(x_i.mul(y_i)))
+}
+
+// payoffs shifted up by a constant so that all are at least one
+pub fn shift_to_positive(payoffs: &[BigRational]) -> Vec<BigRational> {
+    let one = BigRational::one();
+    let min = payoffs.iter().min().unwrap();
+    if *min >= one {
+        return payoffs.to_vec();
+    }
+    let shift = one - min;
+    payoffs.iter().map(|payoff| payoff.add(&shift)).collect()
+}
+
+/*
+ * costs  K - payoffs  with  K  one above the largest payoff, so all are
+ * at least one.  Against a mixed strategy each pure strategy costs  K
+ * minus its payoff, so the best responses are those of least cost, and
+ * the positive cost matrices make the LCPs of a game copositive-plus:
+ * Lemke's algorithm with a positive covering vector finds a solution.
+ */
+pub fn costs(payoffs: &[BigRational]) -> Vec<BigRational> {
+    let k = payoffs.iter().max().unwrap().clone().add(BigRational::one());
+    payoffs.iter().map(|payoff| k.clone().sub(payoff)).collect()
+}
+
+fn is_mixed_strategy(strategy: &[BigRational], len: usize) -> bool {
+    let sum = strategy.iter().fold(BigRational::zero(), |acc, p| acc.add(p));
+    strategy.len() == len && strategy.iter().all(|p| !p.is_negative()) && sum == BigRational::one()
+}
+
+fn is_supported_by_best_responses(strategy: &[BigRational], payoffs: &[BigRational]) -> bool {
+    let best = payoffs.iter().max().unwrap();
+    strategy.iter().zip(payoffs.iter()).all(|(p, payoff)| p.is_zero() || payoff == best)
+}
+
+#[test]
+fn bimatrix_checks_equilibria() {
+
+    // matching pennies
+    let game = Bimatrix::new(2, 2, into_bigrats(vec![1, -1, -1, 1]), into_bigrats(vec![-1, 1, 1, -1]));
+    let half = vec![rat(1, 2), rat(1, 2)];
+    assert!(game.is_equilibrium(&half, &half));
+    assert!(!game.is_equilibrium(&into_bigrats(vec![1, 0]), &half));
+
+    let equilibrium = game.equilibrium(half.clone(), half.clone());
+    assert_eq!(BigRational::zero(), equilibrium.payoff1);
+    assert_eq!(BigRational::zero(), equilibrium.payoff2);
+
+    let positive = game.positive();
+    assert_eq!(into_bigrats(vec![3, 1, 1, 3]), positive.a);
+    assert!(positive.is_equilibrium(&half, &half));
+}
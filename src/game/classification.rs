@@ -0,0 +1,122 @@
+use num::rational::BigRational;
+use num::traits::{Zero,One};
+
+use super::bimatrix::{Bimatrix,Equilibrium};
+use super::lemke_howson::LemkeHowson;
+use super::linear::determinant;
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+#[cfg(test)] use super::equilibrium_graph::lemke_howson_graph;
+#[cfg(test)] use super::lemke_howson::von_stengel_3x2;
+
+/*
+ * pure:          both players play a pure strategy
+ * quasi_strict:  every pure best response is played with positive probability
+ * regular:       quasi-strict, and the equations of best responses on the
+ *                supports have a nonsingular Jacobian (see  classify)
+ * index:         +1 or -1 for regular equilibria, None otherwise
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct Classification {
+    pub pure: bool,
+    pub quasi_strict: bool,
+    pub regular: bool,
+    pub index: Option<i32>,
+}
+
+/*
+ * Classifies an equilibrium with supports  I  of  x  and  J  of  y.
+ * It is regular iff it is quasi-strict,  |I| = |J|  and the matrices
+ *
+ *     [ A_IJ  -1 ]     [ B_IJ'  -1 ]
+ *     [ 1'     0 ]     [ 1'      0 ]
+ *
+ * are nonsingular.  Then, with the payoffs shifted to be positive
+ * (see  Bimatrix::positive),  the index is
+ *
+ *     (-1)^(|I|+1)  sign det A_IJ  sign det B_IJ
+ *
+ * (Shapley 1974, von Schemde and von Stengel 2008), read off the
+ * determinant of the Lemke-Howson tableau at the basis of the
+ * supports, see  LemkeHowson::index.  Lemke-Howson from the
+ * artificial equilibrium (index -1) only ends in equilibria of index
+ * +1,  see  EquilibriumGraph,  and the index sums to one over all
+ * equilibria of a nondegenerate game.  Sequence form equilibria are
+ * not classified: the sequence form is not ported yet.
+ */
+pub fn classify(game: &Bimatrix, equilibrium: &Equilibrium) -> Classification {
+
+    let rows: Vec<usize> = (0..game.m).filter(|&i| !equilibrium.x[i].is_zero()).collect();
+    let cols: Vec<usize> = (0..game.n).filter(|&j| !equilibrium.y[j].is_zero()).collect();
+
+    let pure = rows.len() == 1 && cols.len() == 1;
+    let quasi_strict = best_responses(&game.row_payoffs(&equilibrium.y)) == rows
+        && best_responses(&game.col_payoffs(&equilibrium.x)) == cols;
+
+    let positive = game.positive();
+    let a: Vec<BigRational> = rows.iter().flat_map(|&i| cols.iter().map(move |&j| (i, j))).map(|(i, j)| positive.a(i, j).clone()).collect();
+    let b_transposed: Vec<BigRational> = cols.iter().flat_map(|&j| rows.iter().map(move |&i| (i, j))).map(|(i, j)| positive.b(i, j).clone()).collect();
+
+    let k = rows.len();
+    let regular = quasi_strict && k == cols.len()
+        && !determinant(k + 1, &bordered(k, &a)).is_zero()
+        && !determinant(k + 1, &bordered(k, &b_transposed)).is_zero();
+
+    // with positive payoffs a regular equilibrium has  det A_IJ, det B_IJ != 0
+    let index = if regular {
+        let support: Vec<usize> = rows.iter().cloned().chain(cols.iter().map(|&j| game.m + j)).collect();
+        Some(LemkeHowson::at_support(game, &support).expect("The basis of a regular equilibrium is nonsingular").index())
+    } else {
+        None
+    };
+
+    Classification { pure, quasi_strict, regular, index }
+}
+
+fn best_responses(payoffs: &[BigRational]) -> Vec<usize> {
+    let best = payoffs.iter().max().unwrap();
+    (0..payoffs.len()).filter(|&i| payoffs[i] == *best).collect()
+}
+
+// the  k x k  matrix  a  bordered by  -1  on the right and  1  below
+fn bordered(k: usize, a: &[BigRational]) -> Vec<BigRational> {
+    let mut matrix = Vec::with_capacity((k + 1) * (k + 1));
+    for row in a.chunks(k) {
+        matrix.extend(row.iter().cloned());
+        matrix.push(-BigRational::one());
+    }
+    matrix.extend(vec![BigRational::one(); k]);
+    matrix.push(BigRational::zero());
+    matrix
+}
+
+#[test]
+fn classify_nondegenerate_equilibria() {
+
+    let game = von_stengel_3x2();
+    let graph = lemke_howson_graph(&game);
+
+    for (equilibrium, index) in graph.equilibria.iter().zip(graph.indices.iter()) {
+        let classification = classify(&game, equilibrium);
+        assert!(classification.regular);
+        assert_eq!(Some(*index), classification.index);
+        assert_eq!(equilibrium.x == into_bigrats(vec![1, 0, 0]), classification.pure);
+    }
+    assert_eq!(1, graph.equilibria.iter().map(|equilibrium| classify(&game, equilibrium).index.unwrap()).sum::<i32>());
+}
+
+#[test]
+fn classify_irregular_equilibrium() {
+
+    // the column player is indifferent against the top row
+    let game = Bimatrix::new(2, 2, into_bigrats(vec![1, 1, 0, 2]), into_bigrats(vec![1, 1, 0, 1]));
+
+    let pure = classify(&game, &game.equilibrium(into_bigrats(vec![1, 0]), into_bigrats(vec![1, 0])));
+    assert_eq!(Classification { pure: true, quasi_strict: false, regular: false, index: None }, pure);
+
+    let strict = classify(&game, &game.equilibrium(into_bigrats(vec![0, 1]), into_bigrats(vec![0, 1])));
+    assert_eq!(Classification { pure: true, quasi_strict: true, regular: true, index: Some(1) }, strict);
+
+    let mixed = classify(&game, &game.equilibrium(into_bigrats(vec![1, 0]), vec![rat(1, 2), rat(1, 2)]));
+    assert!(!mixed.pure && !mixed.regular);
+}
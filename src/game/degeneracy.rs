@@ -0,0 +1,81 @@
+use num::rational::BigRational;
+use num::traits::{Zero,Signed};
+
+use std::ops::{Add,Div};
+
+use super::bimatrix::Bimatrix;
+use super::polytope::Polytope;
+
+#[cfg(test)] use lemke::into_bigrats;
+#[cfg(test)] use super::lemke_howson::von_stengel_3x2;
+
+/*
+ * a mixed strategy of  player  (0 for rows, 1 for columns) whose
+ * support is smaller than the set of the opponent's pure best responses
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct DegeneracyWitness {
+    pub player: usize,
+    pub strategy: Vec<BigRational>,
+    pub support: Vec<usize>,
+    pub best_responses: Vec<usize>,
+}
+
+/*
+ * The game is degenerate iff some mixed strategy with support of size  k
+ * has more than  k  pure best responses.  Such a strategy has more
+ * labels than the dimension in the best response polytope, and then so
+ * has a vertex of the face of these labels, so it suffices to check
+ * the vertices of  P  and  Q  (see  extreme_equilibria).  Returns one
+ * witness per such vertex, none if the game is nondegenerate.
+ */
+pub fn degeneracy_witnesses(game: &Bimatrix) -> Vec<DegeneracyWitness> {
+
+    let (m, n) = (game.m, game.n);
+    let positive = game.positive();
+    let b_transposed = (0..n).flat_map(|j| (0..m).map(move |i| (i, j))).map(|(i, j)| positive.b(i, j).clone()).collect();
+
+    let polytopes = vec![
+        (0, Polytope::new(n, m, b_transposed)),
+        (1, Polytope::new(m, n, positive.a.clone())),
+    ];
+
+    let mut witnesses = Vec::new();
+    for (player, polytope) in polytopes {
+        for vertex in polytope.vertices() {
+            let support: Vec<usize> = (0..polytope.d).filter(|&i| vertex.point[i].is_positive()).collect();
+            if support.is_empty() || vertex.tight.len() <= support.len() {
+                continue;
+            }
+            let sum = vertex.point.iter().fold(BigRational::zero(), |acc, u| acc.add(u));
+            witnesses.push(DegeneracyWitness {
+                player,
+                strategy: vertex.point.iter().map(|u| u.div(&sum)).collect(),
+                support,
+                best_responses: vertex.tight,
+            });
+        }
+    }
+    witnesses
+}
+
+pub fn is_degenerate(game: &Bimatrix) -> bool {
+    !degeneracy_witnesses(game).is_empty()
+}
+
+#[test]
+fn degeneracy_is_detected() {
+
+    assert!(!is_degenerate(&von_stengel_3x2()));
+
+    // the column player is indifferent against the top row
+    let game = Bimatrix::new(2, 2, into_bigrats(vec![1, 1, 0, 2]), into_bigrats(vec![1, 1, 0, 1]));
+    let witnesses = degeneracy_witnesses(&game);
+
+    assert_eq!(vec![DegeneracyWitness {
+        player: 0,
+        strategy: into_bigrats(vec![1, 0]),
+        support: vec![0],
+        best_responses: vec![0, 1],
+    }], witnesses);
+}
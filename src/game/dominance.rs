@@ -0,0 +1,234 @@
+use num::rational::BigRational;
+use num::traits::{Zero,One};
+
+use std::ops::{Add,Div,Neg};
+
+use lemke::lp::{maximize,LPResult};
+
+use super::bimatrix::{Bimatrix,Equilibrium,shift_to_positive};
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+#[cfg(test)] use super::support_enumeration::support_enumeration;
+
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum Dominance {
+    // better against every strategy of the opponent
+    Strict,
+    // at least as good against every strategy and better against one
+    Weak,
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub enum Dominator {
+    Pure(usize),
+    // probabilities of the strategies with positive probability
+    Mixed(Vec<(usize, BigRational)>),
+}
+
+/*
+ * strategy  of  player  (0 for rows, 1 for columns) was removed as
+ * dominated by  dominator,  all given as indices in the original game
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct Elimination {
+    pub player: usize,
+    pub strategy: usize,
+    pub dominator: Dominator,
+}
+
+/*
+ * the game left after eliminating dominated strategies:  rows  and
+ * cols  are the original indices of its strategies,  log  the
+ * eliminations in the order they were made
+ */
+#[derive(Debug,Clone)]
+pub struct Reduction {
+    pub game: Bimatrix,
+    pub rows: Vec<usize>,
+    pub cols: Vec<usize>,
+    pub log: Vec<Elimination>,
+}
+
+impl Reduction {
+
+    // an equilibrium of the reduced game as one of the original game
+    pub fn lift(&self, equilibrium: &Equilibrium) -> Equilibrium {
+        let mut x = vec![BigRational::zero(); self.rows.len() + self.removed(0)];
+        let mut y = vec![BigRational::zero(); self.cols.len() + self.removed(1)];
+        for (&i, x_i) in self.rows.iter().zip(equilibrium.x.iter()) {
+            x[i] = x_i.clone();
+        }
+        for (&j, y_j) in self.cols.iter().zip(equilibrium.y.iter()) {
+            y[j] = y_j.clone();
+        }
+        Equilibrium { x, y, payoff1: equilibrium.payoff1.clone(), payoff2: equilibrium.payoff2.clone() }
+    }
+
+    /*
+     * run  solver  on the reduced game and lift its equilibria.  After
+     * strict dominance these are all equilibria of the original game
+     * if  solver  finds all of the reduced game; weak dominance can
+     * remove equilibria, but those returned are still equilibria.
+     */
+    pub fn solve<F>(&self, solver: F) -> Vec<Equilibrium> where F: Fn(&Bimatrix) -> Vec<Equilibrium> {
+        solver(&self.game).iter().map(|equilibrium| self.lift(equilibrium)).collect()
+    }
+
+    fn removed(&self, player: usize) -> usize {
+        self.log.iter().filter(|elimination| elimination.player == player).count()
+    }
+}
+
+/*
+ * Iterated elimination of dominated pure strategies, alternating
+ * between the row and the column player and removing the first
+ * dominated strategy found.  Domination by pure strategies is tried
+ * first; with  mixed  also by mixed strategies, decided exactly by
+ * an LP (see  dominator).  The result of weak dominance can depend on
+ * this order, that of strict dominance does not.
+ */
+pub fn eliminate_dominated(game: &Bimatrix, dominance: Dominance, mixed: bool) -> Reduction {
+
+    let mut rows: Vec<usize> = (0..game.m).collect();
+    let mut cols: Vec<usize> = (0..game.n).collect();
+    let mut log = Vec::new();
+
+    loop {
+        let mut changed = false;
+        for player in 0..2 {
+            // the player's payoffs with own strategies as rows
+            let (own, other) = if player == 0 { (&rows, &cols) } else { (&cols, &rows) };
+            let payoffs: Vec<Vec<BigRational>> = own.iter()
+                .map(|&s| other.iter().map(|&t| if player == 0 { game.a(s, t).clone() } else { game.b(t, s).clone() }).collect())
+                .collect();
+
+            let found = (0..own.len()).filter_map(|s| dominator(&payoffs, s, dominance, mixed).map(|dominator| (s, dominator))).next();
+            if let Some((s, dominator)) = found {
+                let dominator = match dominator {
+                    Dominator::Pure(t) => Dominator::Pure(own[t]),
+                    Dominator::Mixed(sigma) => Dominator::Mixed(sigma.into_iter().map(|(t, p)| (own[t], p)).collect()),
+                };
+                log.push(Elimination { player, strategy: own[s], dominator });
+                if player == 0 { rows.remove(s); } else { cols.remove(s); }
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let a = rows.iter().flat_map(|&i| cols.iter().map(move |&j| (i, j))).map(|(i, j)| game.a(i, j).clone()).collect();
+    let b = rows.iter().flat_map(|&i| cols.iter().map(move |&j| (i, j))).map(|(i, j)| game.b(i, j).clone()).collect();
+    Reduction { game: Bimatrix::new(rows.len(), cols.len(), a, b), rows, cols, log }
+}
+
+/*
+ * a strategy dominating  s,  where  payoffs[s][t]  is the payoff of
+ * own strategy  s  against  t.  Mixed dominators  sigma  over the
+ * other strategies (with payoffs shifted to be positive) solve
+ *
+ *     strict:  min 1'p  s.t.  p'A >= A[s],  p >= 0,   sigma = p / 1'p
+ *              s  is dominated iff  1'p < 1
+ *     weak:    max sigma'A 1  s.t.  sigma'A >= A[s],  1'sigma = 1,  sigma >= 0
+ *              s  is dominated iff the LP is feasible and  sigma'A 1 > A[s] 1
+ */
+fn dominator(payoffs: &[Vec<BigRational>], s: usize, dominance: Dominance, mixed: bool) -> Option<Dominator> {
+
+    let dominates = |t: &[BigRational]| -> bool {
+        let better = t.iter().zip(payoffs[s].iter()).filter(|&(t_j, s_j)| t_j > s_j).count();
+        let worse = t.iter().zip(payoffs[s].iter()).filter(|&(t_j, s_j)| t_j < s_j).count();
+        match dominance {
+            Dominance::Strict => better == t.len(),
+            Dominance::Weak => worse == 0 && better > 0,
+        }
+    };
+    if let Some(t) = (0..payoffs.len()).find(|&t| t != s && dominates(&payoffs[t])) {
+        return Some(Dominator::Pure(t));
+    }
+
+    let others: Vec<usize> = (0..payoffs.len()).filter(|&t| t != s).collect();
+    if !mixed || others.len() < 2 {
+        return None;
+    }
+
+    let k = others.len();
+    let n = payoffs[s].len();
+    let flat: Vec<BigRational> = payoffs.iter().flat_map(|row| row.iter().cloned()).collect();
+    let positive: Vec<Vec<BigRational>> = shift_to_positive(&flat).chunks(n).map(|row| row.to_vec()).collect();
+
+    // rows  -p'A[.][j] <= -A[s][j]
+    let mut a: Vec<BigRational> = (0..n).flat_map(|j| others.iter().map(|&t| positive[t][j].clone().neg()).collect::<Vec<_>>()).collect();
+    let mut b: Vec<BigRational> = positive[s].iter().map(|a_sj| a_sj.clone().neg()).collect();
+
+    let sigma = match dominance {
+        Dominance::Strict => {
+            match maximize(a, b, vec![BigRational::one().neg(); k]) {
+                LPResult::Optimal { x: p, value, .. } if value > BigRational::one().neg() => {
+                    let sum = value.neg();
+                    p.iter().map(|p_t| p_t.div(&sum)).collect::<Vec<_>>()
+                },
+                _ => return None,
+            }
+        },
+        Dominance::Weak => {
+            a.extend(vec![BigRational::one(); k]);
+            a.extend(vec![BigRational::one().neg(); k]);
+            b.push(BigRational::one());
+            b.push(BigRational::one().neg());
+            let c: Vec<BigRational> = others.iter().map(|&t| positive[t].iter().fold(BigRational::zero(), |acc, a_tj| acc.add(a_tj))).collect();
+            let total_s = positive[s].iter().fold(BigRational::zero(), |acc, a_sj| acc.add(a_sj));
+            match maximize(a, b, c) {
+                LPResult::Optimal { x: sigma, value, .. } if value > total_s => sigma,
+                _ => return None,
+            }
+        },
+    };
+
+    Some(Dominator::Mixed(others.into_iter().zip(sigma).filter(|(_, p)| !p.is_zero()).collect()))
+}
+
+#[test]
+fn dominance_eliminates_iteratively() {
+
+    // the bottom row is dominated by mixing the others, then the right
+    // column and then the middle row by pure strategies
+    let game = Bimatrix::new(3, 2, into_bigrats(vec![3, 0, 0, 3, 1, 1]), into_bigrats(vec![2, 0, 1, 0, 0, 3]));
+    let reduction = eliminate_dominated(&game, Dominance::Strict, true);
+
+    assert_eq!(vec![
+        Elimination { player: 0, strategy: 2, dominator: Dominator::Mixed(vec![(0, rat(1, 2)), (1, rat(1, 2))]) },
+        Elimination { player: 1, strategy: 1, dominator: Dominator::Pure(0) },
+        Elimination { player: 0, strategy: 1, dominator: Dominator::Pure(0) },
+    ], reduction.log);
+    assert_eq!(vec![0], reduction.rows);
+    assert_eq!(vec![0], reduction.cols);
+
+    let equilibria = reduction.solve(support_enumeration);
+    assert_eq!(vec![game.equilibrium(into_bigrats(vec![1, 0, 0]), into_bigrats(vec![1, 0]))], equilibria);
+    assert_eq!(equilibria, support_enumeration(&game));
+
+    // without mixed dominance nothing is dominated
+    assert!(eliminate_dominated(&game, Dominance::Strict, false).log.is_empty());
+}
+
+#[test]
+fn dominance_weak() {
+
+    // after the bottom row the columns are equally good
+    let game = Bimatrix::new(2, 2, into_bigrats(vec![1, 1, 1, 0]), into_bigrats(vec![1, 1, 1, 0]));
+    assert!(eliminate_dominated(&game, Dominance::Strict, true).log.is_empty());
+
+    let reduction = eliminate_dominated(&game, Dominance::Weak, true);
+    assert_eq!(vec![Elimination { player: 0, strategy: 1, dominator: Dominator::Pure(0) }], reduction.log);
+    assert_eq!(vec![0, 1], reduction.cols);
+
+    // weakly dominated by a mix of the first two columns only
+    let game = Bimatrix::new(2, 3, into_bigrats(vec![0, 0, 0, 0, 0, 0]), into_bigrats(vec![2, 0, 1, 0, 2, 1]));
+    let reduction = eliminate_dominated(&game, Dominance::Weak, true);
+    assert!(reduction.log.is_empty());
+    let game = Bimatrix::new(2, 3, into_bigrats(vec![0, 0, 0, 0, 0, 0]), into_bigrats(vec![2, 0, 1, 0, 3, 1]));
+    let reduction = eliminate_dominated(&game, Dominance::Weak, true);
+    assert_eq!(1, reduction.log.len());
+    assert_eq!(2, reduction.log[0].strategy);
+}
@@ -0,0 +1,131 @@
+use std::collections::VecDeque;
+
+use super::bimatrix::{Bimatrix,Equilibrium};
+use super::lemke_howson::LemkeHowson;
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+#[cfg(test)] use super::lemke_howson::von_stengel_3x2;
+
+/*
+ * one Lemke-Howson path: dropping  label  at  from  ends at  to,
+ * where None stands for the artificial equilibrium
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct LabelEdge {
+    pub from: Option<usize>,
+    pub label: usize,
+    pub to: Option<usize>,
+}
+
+/*
+ * The component of the Lemke-Howson graph that contains the
+ * artificial equilibrium.  indices[e]  is the index of  equilibria[e],
+ * counting the artificial equilibrium as  -1:  every path joins
+ * equilibria of opposite index, so in a nondegenerate game the found
+ * equilibria of index  +1  outnumber those of index  -1  by one.
+ */
+#[derive(Debug,Clone)]
+pub struct EquilibriumGraph {
+    pub equilibria: Vec<Equilibrium>,
+    pub indices: Vec<i32>,
+    pub edges: Vec<LabelEdge>,
+}
+
+impl EquilibriumGraph {
+
+    pub fn has_multiple_equilibria(&self) -> bool {
+        self.equilibria.len() > 1
+    }
+
+    // the edges leaving  from  (None for the artificial equilibrium)
+    pub fn edges_from(&self, from: Option<usize>) -> Vec<&LabelEdge> {
+        self.edges.iter().filter(|edge| edge.from == from).collect()
+    }
+}
+
+/*
+ * Lemke-Howson from the artificial equilibrium with every missing
+ * label, then again from every equilibrium found with every label,
+ * until no new equilibria turn up
+ */
+pub fn lemke_howson_graph(game: &Bimatrix) -> EquilibriumGraph {
+
+    let labels = game.m + game.n;
+    let mut graph = EquilibriumGraph {
+        equilibria: Vec::new(),
+        indices: Vec::new(),
+        edges: Vec::new(),
+    };
+
+    // complementary bases to start from, with their equilibrium
+    let mut queue: VecDeque<(Option<usize>, LemkeHowson)> = VecDeque::new();
+    queue.push_back((None, LemkeHowson::new(game)));
+
+    while let Some((from, start)) = queue.pop_front() {
+        let index = match from {
+            Some(e) => graph.indices[e],
+            None => -1,
+        };
+
+        for label in 0..labels {
+            let mut lemke_howson = start.clone();
+            lemke_howson.drop_label(label);
+
+            let to = if lemke_howson.is_artificial() {
+                None
+            } else {
+                let equilibrium = lemke_howson.equilibrium();
+                match graph.equilibria.iter().position(|found| *found == equilibrium) {
+                    Some(e) => Some(e),
+                    None => {
+                        graph.equilibria.push(equilibrium);
+                        graph.indices.push(-index);
+                        let e = graph.equilibria.len() - 1;
+                        queue.push_back((Some(e), lemke_howson));
+                        Some(e)
+                    }
+                }
+            };
+            graph.edges.push(LabelEdge { from, label, to });
+        }
+    }
+
+    graph
+}
+
+#[test]
+fn lemke_howson_graph_finds_all_equilibria() {
+
+    let game = von_stengel_3x2();
+    let graph = lemke_howson_graph(&game);
+
+    assert!(graph.has_multiple_equilibria());
+    assert_eq!(3, graph.equilibria.len());
+    assert_eq!(4 * 5, graph.edges.len());
+
+    let index_of = |x: Vec<_>| {
+        let e = graph.equilibria.iter().position(|equilibrium| equilibrium.x == x).unwrap();
+        graph.indices[e]
+    };
+    assert_eq!(1, index_of(into_bigrats(vec![1, 0, 0])));
+    assert_eq!(-1, index_of(vec![rat(4, 5), rat(1, 5), rat(0, 1)]));
+    assert_eq!(1, index_of(vec![rat(0, 1), rat(1, 3), rat(2, 3)]));
+
+    // paths can be followed both ways
+    for edge in graph.edges.iter() {
+        let back = LabelEdge { from: edge.to, label: edge.label, to: edge.from };
+        assert!(graph.edges.contains(&back));
+    }
+    assert_eq!(5, graph.edges_from(None).len());
+}
+
+#[test]
+fn lemke_howson_graph_of_unique_equilibrium() {
+
+    let game = Bimatrix::new(2, 2, into_bigrats(vec![1, -1, -1, 1]), into_bigrats(vec![-1, 1, 1, -1]));
+    let graph = lemke_howson_graph(&game);
+
+    assert!(!graph.has_multiple_equilibria());
+    assert_eq!(vec![1], graph.indices);
+    assert!(graph.edges.iter().all(|edge| edge.to.is_none() || edge.from.is_none()));
+}
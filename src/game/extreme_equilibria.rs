@@ -0,0 +1,207 @@
+use num::rational::BigRational;
+use num::traits::Zero;
+
+use std::ops::{Add,Div};
+
+use super::bimatrix::{Bimatrix,Equilibrium};
+use super::polytope::{Polytope,Vertex};
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+#[cfg(test)] use super::lemke_howson::von_stengel_3x2;
+
+/*
+ * maximal Nash subset: every pair of  x_strategies[i], i in x  and
+ * y_strategies[j], j in y  is an equilibrium, and no strategy can be
+ * added to either side
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct NashSubset {
+    pub x: Vec<usize>,
+    pub y: Vec<usize>,
+}
+
+/*
+ * All extreme equilibria of a bimatrix game.  x_strategies  and
+ * y_strategies  are the extreme mixed strategies that occur in them,
+ * pairs[e]  indexes both for  equilibria[e].  The set of equilibria is
+ * the union of the convex hulls of the maximal Nash subsets;  subsets
+ * sharing a strategy lie in the same connected component.
+ */
+#[derive(Debug,Clone)]
+pub struct ExtremeEquilibria {
+    pub x_strategies: Vec<Vec<BigRational>>,
+    pub y_strategies: Vec<Vec<BigRational>>,
+    pub pairs: Vec<(usize, usize)>,
+    pub equilibria: Vec<Equilibrium>,
+    pub subsets: Vec<NashSubset>,
+}
+
+impl ExtremeEquilibria {
+
+    // maximal Nash subsets grouped into the connected components of the equilibria
+    pub fn components(&self) -> Vec<Vec<&NashSubset>> {
+
+        let mut components: Vec<Vec<&NashSubset>> = Vec::new();
+        for subset in self.subsets.iter() {
+            let (joined, mut rest): (Vec<_>, Vec<_>) = components.into_iter()
+                .partition(|component| component.iter().any(|other| overlaps(subset, other)));
+            let mut component: Vec<&NashSubset> = joined.into_iter().flatten().collect();
+            component.push(subset);
+            rest.push(component);
+            components = rest;
+        }
+        components
+    }
+}
+
+fn overlaps(a: &NashSubset, b: &NashSubset) -> bool {
+    a.x.iter().any(|i| b.x.contains(i)) || a.y.iter().any(|j| b.y.contains(j))
+}
+
+/*
+ * Enumerates the vertices of the best response polytopes
+ *
+ *     P = { x >= 0 : B'x <= 1 },   Q = { y >= 0 : Ay <= 1 }
+ *
+ * for the positive payoffs of  Bimatrix::positive  and keeps the
+ * completely labeled pairs other than  (0, 0).  Label  i < m  is
+ * x(i) = 0  or  (Ay)(i) = 1,  label  m + j  is  (B'x)(j) = 1  or  y(j) = 0.
+ * The maximal Nash subsets are the maximal complete bipartite
+ * subgraphs (cliques) of the graph of extreme equilibria.
+ */
+pub fn extreme_equilibria(game: &Bimatrix) -> ExtremeEquilibria {
+
+    let (m, n) = (game.m, game.n);
+    let positive = game.positive();
+
+    let b_transposed = (0..n).flat_map(|j| (0..m).map(move |i| (i, j))).map(|(i, j)| positive.b(i, j).clone()).collect();
+    let p = Polytope::new(n, m, b_transposed).vertices();
+    let q = Polytope::new(m, n, positive.a.clone()).vertices();
+
+    let labels_p = |vertex: &Vertex| -> Vec<usize> {
+        vertex.zeros.iter().cloned().chain(vertex.tight.iter().map(|j| m + j)).collect()
+    };
+    let labels_q = |vertex: &Vertex| -> Vec<usize> {
+        vertex.tight.iter().cloned().chain(vertex.zeros.iter().map(|j| m + j)).collect()
+    };
+
+    let mut extreme = ExtremeEquilibria {
+        x_strategies: Vec::new(),
+        y_strategies: Vec::new(),
+        pairs: Vec::new(),
+        equilibria: Vec::new(),
+        subsets: Vec::new(),
+    };
+
+    for u in p.iter().filter(|u| !is_origin(u)) {
+        let labels_u = labels_p(u);
+        for v in q.iter().filter(|v| !is_origin(v)) {
+            let labels_v = labels_q(v);
+            if (0..m + n).all(|label| labels_u.contains(&label) || labels_v.contains(&label)) {
+                let x = normalize(&u.point);
+                let y = normalize(&v.point);
+                let pair = (index_of(&mut extreme.x_strategies, &x), index_of(&mut extreme.y_strategies, &y));
+                extreme.pairs.push(pair);
+                extreme.equilibria.push(game.equilibrium(x, y));
+            }
+        }
+    }
+
+    extreme.subsets = maximal_cliques(&extreme.pairs);
+    extreme
+}
+
+fn is_origin(vertex: &Vertex) -> bool {
+    vertex.point.iter().all(|u| u.is_zero())
+}
+
+fn normalize(u: &[BigRational]) -> Vec<BigRational> {
+    let sum = u.iter().fold(BigRational::zero(), |acc, u_i| acc.add(u_i));
+    u.iter().map(|u_i| u_i.div(&sum)).collect()
+}
+
+fn index_of(strategies: &mut Vec<Vec<BigRational>>, strategy: &[BigRational]) -> usize {
+    match strategies.iter().position(|known| *known == strategy) {
+        Some(index) => index,
+        None => {
+            strategies.push(strategy.to_vec());
+            strategies.len() - 1
+        },
+    }
+}
+
+/*
+ * The maximal bicliques  (X, Y)  of the bipartite graph with edges
+ * pairs  are those where  Y  is the set of common neighbours of  X  and
+ * vice versa.  Every such  Y  is an intersection of neighbourhoods of
+ * single  x,  so close these under intersection.
+ */
+fn maximal_cliques(pairs: &[(usize, usize)]) -> Vec<NashSubset> {
+
+    let neighbours_x = |x: usize| -> Vec<usize> {
+        pairs.iter().filter(|pair| pair.0 == x).map(|pair| pair.1).collect()
+    };
+    let mut xs: Vec<usize> = pairs.iter().map(|pair| pair.0).collect();
+    xs.sort();
+    xs.dedup();
+
+    let mut ys: Vec<Vec<usize>> = Vec::new();
+    for x in xs {
+        let y = neighbours_x(x);
+        if !ys.contains(&y) {
+            ys.push(y);
+        }
+    }
+    let mut i = 0;
+    while i < ys.len() {
+        for j in 0..i {
+            let y: Vec<usize> = ys[i].iter().filter(|y| ys[j].contains(y)).cloned().collect();
+            if !y.is_empty() && !ys.contains(&y) {
+                ys.push(y);
+            }
+        }
+        i += 1;
+    }
+
+    ys.into_iter().map(|y| {
+        let mut x: Vec<usize> = pairs.iter().map(|pair| pair.0)
+            .filter(|x| y.iter().all(|y| pairs.contains(&(*x, *y))))
+            .collect();
+        x.sort();
+        x.dedup();
+        NashSubset { x, y }
+    }).collect()
+}
+
+#[test]
+fn extreme_equilibria_of_nondegenerate_game() {
+
+    let game = von_stengel_3x2();
+    let extreme = extreme_equilibria(&game);
+
+    assert_eq!(3, extreme.equilibria.len());
+    assert_eq!(3, extreme.subsets.len());
+    assert_eq!(3, extreme.components().len());
+    for equilibrium in extreme.equilibria.iter() {
+        assert!(game.is_equilibrium(&equilibrium.x, &equilibrium.y));
+    }
+    assert!(extreme.x_strategies.contains(&vec![rat(4, 5), rat(1, 5), rat(0, 1)]));
+}
+
+#[test]
+fn extreme_equilibria_of_degenerate_game() {
+
+    // the column player is indifferent when the row player plays the
+    // top row, so  (top, y)  is an equilibrium for all  y  with  y(0) >= 1/2
+    let game = Bimatrix::new(2, 2, into_bigrats(vec![1, 1, 0, 2]), into_bigrats(vec![1, 1, 0, 1]));
+    let extreme = extreme_equilibria(&game);
+
+    for equilibrium in extreme.equilibria.iter() {
+        assert!(game.is_equilibrium(&equilibrium.x, &equilibrium.y));
+    }
+    let top = extreme.x_strategies.iter().position(|x| *x == into_bigrats(vec![1, 0])).unwrap();
+    let subset = extreme.subsets.iter().find(|subset| subset.x == vec![top]).unwrap();
+    let mut ys: Vec<Vec<BigRational>> = subset.y.iter().map(|&j| extreme.y_strategies[j].clone()).collect();
+    ys.sort();
+    assert_eq!(vec![vec![rat(1, 2), rat(1, 2)], into_bigrats(vec![1, 0])], ys);
+}
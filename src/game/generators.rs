@@ -0,0 +1,381 @@
+use num::bigint::BigInt;
+use num::rational::BigRational;
+use num::traits::{Zero,One,Signed};
+
+use std::ops::{Div,Mul,Neg,Sub};
+
+use rand_chacha::ChaCha8Rng;
+use rand_chacha::rand_core::{RngCore,SeedableRng};
+
+use super::bimatrix::Bimatrix;
+use super::linear::solve;
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+#[cfg(test)] use super::degeneracy::is_degenerate;
+#[cfg(test)] use super::lemke_howson::LemkeHowson;
+#[cfg(test)] use super::support_enumeration::support_enumeration;
+
+/*
+ * random games for benchmarks and tests, reproducible from  seed.
+ * Payoffs are integers in  low..=high  unless said otherwise.  All draws
+ * come from the raw output of ChaCha8 (rand_chacha  is pinned), so a
+ * seed gives the same games on every platform and build.
+ */
+pub struct GameGenerator {
+    rng: ChaCha8Rng,
+}
+
+impl GameGenerator {
+
+    pub fn new(seed: u64) -> GameGenerator {
+        GameGenerator { rng: ChaCha8Rng::seed_from_u64(seed) }
+    }
+
+    // uniform in  0..n  by rejecting the incomplete last block of  2^64
+    fn below(&mut self, n: u64) -> u64 {
+        let limit = u64::MAX - u64::MAX % n;
+        loop {
+            let draw = self.rng.next_u64();
+            if draw < limit {
+                return draw % n;
+            }
+        }
+    }
+
+    fn index(&mut self, n: usize) -> usize {
+        self.below(n as u64) as usize
+    }
+
+    fn payoff(&mut self, low: i64, high: i64) -> BigRational {
+        if low > high {
+            panic!("No payoffs between {} and {}", low, high);
+        }
+        let span = high.wrapping_sub(low) as u64;
+        let offset = if span == u64::MAX { self.rng.next_u64() } else { self.below(span + 1) };
+        integer(low.wrapping_add(offset as i64))
+    }
+
+    fn payoffs(&mut self, len: usize, low: i64, high: i64) -> Vec<BigRational> {
+        (0..len).map(|_| self.payoff(low, high)).collect()
+    }
+
+    // independent uniform payoffs for both players
+    pub fn uniform(&mut self, m: usize, n: usize, low: i64, high: i64) -> Bimatrix {
+        let a = self.payoffs(m * n, low, high);
+        let b = self.payoffs(m * n, low, high);
+        Bimatrix::new(m, n, a, b)
+    }
+
+    /*
+     * payoffs  (a, b)  of each cell approximately normal with the given
+     * correlation, rounded to integers in  -range..=range  (three
+     * standard deviations).  Correlation 1 is a common interest game,
+     * -1  a zero-sum game.  Only integer draws and rational arithmetic
+     * are used, so the games are the same on every platform.
+     */
+    pub fn covariant(&mut self, m: usize, n: usize, correlation: &BigRational, range: i64) -> Bimatrix {
+        if correlation.abs() > BigRational::one() {
+            panic!("Correlation must be between -1 and 1 but is {}", correlation);
+        }
+        let scale = integer(range).div(integer(3));
+        let round = |value: BigRational| value.mul(&scale).round().max(integer(-range)).min(integer(range));
+
+        // sqrt(1 - r^2)  to 32 bits
+        let precision = BigInt::one() << 32;
+        let complement = (BigRational::one() - correlation * correlation) * BigRational::from_integer(&precision * &precision);
+        let complement = BigRational::new(complement.to_integer().sqrt(), precision);
+
+        let (mut a, mut b) = (Vec::with_capacity(m * n), Vec::with_capacity(m * n));
+        for _ in 0..m * n {
+            let (z1, z2) = (self.normal(), self.normal());
+            b.push(round(correlation * &z1 + &complement * z2));
+            a.push(round(z1));
+        }
+        Bimatrix::new(m, n, a, b)
+    }
+
+    // mean 0 and variance 1 as the sum of 12 uniform draws (Irwin-Hall)
+    fn normal(&mut self) -> BigRational {
+        let units = 1i64 << 20;
+        let sum: i64 = (0..12).map(|_| self.below(units as u64) as i64).sum();
+        BigRational::new(BigInt::from(sum - 6 * units), BigInt::from(units))
+    }
+
+    /*
+     * n x n  game with  A = B  paying  1..=high  on the diagonal and 0
+     * elsewhere:  both players mixing on the same nonempty support to
+     * equalize the diagonal payoffs is an equilibrium,  2^n - 1  in all
+     */
+    pub fn coordination(&mut self, n: usize, high: i64) -> Bimatrix {
+        let mut a = vec![BigRational::zero(); n * n];
+        for i in 0..n {
+            a[i*n + i] = self.payoff(1, high);
+        }
+        Bimatrix::new(n, n, a.clone(), a)
+    }
+
+    // uniform payoffs with  B = -A
+    pub fn zero_sum(&mut self, m: usize, n: usize, low: i64, high: i64) -> Bimatrix {
+        let a = self.payoffs(m * n, low, high);
+        let b = a.iter().map(|a_ij| a_ij.clone().neg()).collect();
+        Bimatrix::new(m, n, a, b)
+    }
+
+    /*
+     * uniform payoffs where two rows tie for the best response to some
+     * pure column, so the game is degenerate
+     */
+    pub fn degenerate(&mut self, m: usize, n: usize, low: i64, high: i64) -> Bimatrix {
+        if m < 2 {
+            panic!("A degenerate game needs two rows but has {}", m);
+        }
+        let mut game = self.uniform(m, n, low, high);
+        let j = self.index(n);
+        let i1 = self.index(m);
+        let i2 = (i1 + 1 + self.index(m - 1)) % m;
+        let best = (0..m).map(|i| game.a(i, j).clone()).max().unwrap();
+        game.a[i1*n + j] = best.clone();
+        game.a[i2*n + j] = best;
+        game
+    }
+
+    /*
+     * random game tree of the given depth with  2..=max_moves  moves at
+     * each decision node and uniform payoffs at the leaves, see  GameTree
+     */
+    pub fn game_tree(&mut self, depth: usize, max_moves: usize, low: i64, high: i64) -> GameTree {
+        if max_moves < 2 {
+            panic!("Decision nodes need at least two moves but at most {} were allowed", max_moves);
+        }
+
+        let mut tree = GameTree { nodes: vec![TreeNode::Leaf { payoffs: (BigRational::zero(), BigRational::zero()) }], isets: Vec::new() };
+        // nodes of the current level with the last own  (iset, move)  of each player
+        let mut level: Vec<(usize, History)> = vec![(0, [None, None])];
+
+        for depth in 0..depth {
+            let player = depth % 2;
+            let mut next = Vec::new();
+            // isets of this level by own history, which they must share (perfect recall)
+            let mut open: Vec<(Option<(usize, usize)>, usize)> = Vec::new();
+            for (node, history) in level {
+                let own = history[player];
+                let joinable: Vec<usize> = open.iter().filter(|&&(h, _)| h == own).map(|&(_, iset)| iset).collect();
+                let iset = if !joinable.is_empty() && self.below(2) == 1 {
+                    joinable[self.index(joinable.len())]
+                } else {
+                    let moves = 2 + self.index(max_moves - 1);
+                    tree.isets.push(InformationSet { player, moves, nodes: Vec::new() });
+                    open.push((own, tree.isets.len() - 1));
+                    tree.isets.len() - 1
+                };
+                tree.isets[iset].nodes.push(node);
+
+                let children: Vec<usize> = (0..tree.isets[iset].moves).map(|mv| {
+                    let mut history = history;
+                    history[player] = Some((iset, mv));
+                    tree.nodes.push(TreeNode::Leaf { payoffs: (BigRational::zero(), BigRational::zero()) });
+                    next.push((tree.nodes.len() - 1, history));
+                    tree.nodes.len() - 1
+                }).collect();
+                tree.nodes[node] = TreeNode::Decision { iset, children };
+            }
+            level = next;
+        }
+
+        for (node, _) in level {
+            let payoffs = (self.payoff(low, high), self.payoff(low, high));
+            tree.nodes[node] = TreeNode::Leaf { payoffs };
+        }
+        tree
+    }
+}
+
+type History = [Option<(usize, usize)>; 2];
+
+fn integer(value: i64) -> BigRational {
+    BigRational::from_integer(BigInt::from(value))
+}
+
+/*
+ * Imitation game  (I, C')  where  {x >= 0 : Cx <= 1}  is the dual cyclic
+ * polytope in dimension  d  with  2d  facets labeled as by Morris (1994),
+ * the first  d  by  1..d  and the others by  d, d-2, d-1, .., 2, 3, 1,
+ * see Savani and von Stengel (2006).  The Lemke-Howson paths for labels
+ * 1  and  d  of either player take  12, 32, 80, 196, ..  pivots for
+ * d = 4, 6, 8, 10, ..  (d  even), exponentially many.
+ */
+pub fn hard_for_lemke_howson(d: usize) -> Bimatrix {
+    if d == 0 || d % 2 == 1 {
+        panic!("The dimension must be even and positive but is {}", d);
+    }
+
+    // facet normals  mu(t) - centroid  on the moment curve, t = 1..2d
+    let moments: Vec<Vec<BigRational>> = (1..2*d as i64 + 1).map(|t| (1..d as u32 + 1).map(|k| integer(t.pow(k))).collect()).collect();
+    let count = integer(2 * d as i64);
+    let centroid: Vec<BigRational> = (0..d).map(|k| moments.iter().fold(BigRational::zero(), |acc, mu| acc + &mu[k]).div(&count)).collect();
+    let normals: Vec<Vec<BigRational>> = moments.iter().map(|mu| mu.iter().zip(centroid.iter()).map(|(mu_k, c_k)| mu_k.clone().sub(c_k)).collect()).collect();
+
+    // with  y = 1 - Fx  for the first  d  facets  F,  Gx <= 1  becomes
+    // -GF^-1 y <= 1 - GF^-1 1
+    let f: Vec<BigRational> = normals[..d].iter().flat_map(|row| row.iter().cloned()).collect();
+    let columns: Vec<Vec<BigRational>> = (0..d).map(|k| {
+        let unit: Vec<BigRational> = (0..d).map(|i| if i == k { BigRational::one() } else { BigRational::zero() }).collect();
+        solve(d, &f, &unit).expect("The facets of a vertex are independent").0
+    }).collect();
+    let g_f_inverse = |row: &[BigRational], k: usize| row.iter().zip(columns[k].iter()).fold(BigRational::zero(), |acc, (g, f)| acc + g * f);
+
+    // labels  d, d-2, d-1, d-4, d-3, .., 1  of the last  d  facets
+    let mut labels = vec![d - 1];
+    labels.extend((1..d - 2).rev().step_by(2).flat_map(|i| vec![i, i + 1]));
+    labels.push(0);
+    let mut c = vec![BigRational::zero(); d * d];
+    for (&label, normal) in labels.iter().zip(normals[d..].iter()) {
+        let row: Vec<BigRational> = (0..d).map(|k| g_f_inverse(normal, k).neg()).collect();
+        let rhs = row.iter().fold(BigRational::one(), |acc, r| acc + r);
+        for k in 0..d {
+            c[label*d + k] = row[k].clone().div(&rhs);
+        }
+    }
+
+    let identity = (0..d * d).map(|e| if e / d == e % d { BigRational::one() } else { BigRational::zero() }).collect();
+    let transpose = (0..d * d).map(|e| c[(e % d)*d + e / d].clone()).collect();
+    Bimatrix::new(d, d, identity, transpose)
+}
+
+/*
+ * A finite two-player game tree.  ExtensiveForm  cannot build trees
+ * yet, so generated trees use this plain form: nodes by index with the
+ * root at 0, decision nodes in information sets of the player to move.
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct GameTree {
+    pub nodes: Vec<TreeNode>,
+    pub isets: Vec<InformationSet>,
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub enum TreeNode {
+    // child  k  follows move  k  of the information set
+    Decision { iset: usize, children: Vec<usize> },
+    Leaf { payoffs: (BigRational, BigRational) },
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct InformationSet {
+    pub player: usize,
+    pub moves: usize,
+    pub nodes: Vec<usize>,
+}
+
+impl GameTree {
+
+    /*
+     * the own  (iset, move)  sequences of  player  leading to each node;
+     * with perfect recall these agree on every information set of  player
+     */
+    pub fn sequences(&self, player: usize) -> Vec<Vec<(usize, usize)>> {
+        let mut sequences = vec![Vec::new(); self.nodes.len()];
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            if let TreeNode::Decision { iset, ref children } = self.nodes[node] {
+                for (mv, &child) in children.iter().enumerate() {
+                    let mut sequence = sequences[node].clone();
+                    if self.isets[iset].player == player {
+                        sequence.push((iset, mv));
+                    }
+                    sequences[child] = sequence;
+                    stack.push(child);
+                }
+            }
+        }
+        sequences
+    }
+
+    pub fn has_perfect_recall(&self) -> bool {
+        (0..2).all(|player| {
+            let sequences = self.sequences(player);
+            self.isets.iter().filter(|h| h.player == player).all(|h| h.nodes.iter().all(|&node| sequences[node] == sequences[h.nodes[0]]))
+        })
+    }
+}
+
+#[test]
+fn generators_are_reproducible() {
+
+    let (mut first, mut second) = (GameGenerator::new(7), GameGenerator::new(7));
+    assert_eq!(first.uniform(3, 4, -5, 5), second.uniform(3, 4, -5, 5));
+    assert_eq!(first.covariant(3, 3, &rat(1, 2), 10), second.covariant(3, 3, &rat(1, 2), 10));
+    assert_eq!(first.game_tree(4, 3, 0, 9), second.game_tree(4, 3, 0, 9));
+    assert!(GameGenerator::new(8).uniform(3, 4, -5, 5) != GameGenerator::new(7).uniform(3, 4, -5, 5));
+}
+
+#[test]
+fn generators_have_their_structure() {
+
+    let mut generator = GameGenerator::new(1);
+
+    let game = generator.uniform(4, 3, -2, 2);
+    assert!(game.a.iter().chain(game.b.iter()).all(|p| *p >= integer(-2) && *p <= integer(2)));
+
+    let game = generator.covariant(4, 4, &rat(1, 1), 10);
+    assert_eq!(game.a, game.b);
+    let game = generator.covariant(4, 4, &rat(-1, 1), 10);
+    assert!(game.a.iter().zip(game.b.iter()).all(|(a, b)| *a == -b));
+
+    let game = generator.zero_sum(3, 5, -9, 9);
+    assert!(game.a.iter().zip(game.b.iter()).all(|(a, b)| *a == -b));
+
+    assert_eq!(7, support_enumeration(&generator.coordination(3, 5)).len());
+
+    for _ in 0..10 {
+        assert!(is_degenerate(&generator.degenerate(3, 3, 0, 9)));
+    }
+}
+
+#[test]
+fn generated_trees_have_perfect_recall() {
+
+    for seed in 0..10 {
+        let tree = GameGenerator::new(seed).game_tree(5, 3, -5, 5);
+        assert!(tree.has_perfect_recall());
+        for h in tree.isets.iter() {
+            assert!(h.nodes.iter().all(|&node| match tree.nodes[node] {
+                TreeNode::Decision { iset, ref children } => tree.isets[iset] == *h && children.len() == h.moves,
+                TreeNode::Leaf { .. } => false,
+            }));
+        }
+    }
+}
+
+#[test]
+fn hard_games_have_long_lemke_howson_paths() {
+
+    // twice the Lemke paths of Morris, growing exponentially
+    for (d, longest) in [(2, 4), (4, 12), (6, 32), (8, 80)] {
+        let game = hard_for_lemke_howson(d);
+        for label in 0..2*d {
+            let mut lemke_howson = LemkeHowson::new(&game);
+            let pivots = lemke_howson.drop_label(label);
+            assert!(pivots <= longest);
+            assert_eq!(label % d == 0 || label % d == d - 1, pivots == longest);
+
+            let equilibrium = lemke_howson.equilibrium();
+            assert!(game.is_equilibrium(&equilibrium.x, &equilibrium.y));
+        }
+    }
+}
+
+
+// changes to the generator or its dependencies must not change the games
+#[test]
+fn generators_give_pinned_games() {
+
+    let mut generator = GameGenerator::new(7);
+    let game = generator.uniform(2, 3, -9, 9);
+    assert_eq!(into_bigrats(vec![-4, -1, -7, -6, -1, 9]), game.a);
+    assert_eq!(into_bigrats(vec![-8, -8, 5, 9, -5, -2]), game.b);
+
+    let game = generator.covariant(2, 2, &rat(1, 2), 10);
+    assert_eq!(into_bigrats(vec![-2, 7, 3, -3]), game.a);
+    assert_eq!(into_bigrats(vec![0, 4, 1, -7]), game.b);
+}
@@ -0,0 +1,303 @@
+use num::bigint::BigInt;
+use num::integer::Integer;
+use num::rational::{Ratio,BigRational};
+use num::traits::{Zero,One,Signed};
+
+use std::ops::{Add,Div,Mul};
+
+use lemke::{Tableau,TableauVariable,TableauVariables,lexminratio};
+
+use super::bimatrix::{Bimatrix,Equilibrium};
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+
+/*
+ * Lemke-Howson on the best response polytopes of a bimatrix game
+ * with positive payoffs  A, B  (see  Bimatrix::positive)
+ *
+ *     w = 1 - [ 0  A ] z >= 0,   z = [ x ] >= 0
+ *             [ B' 0 ]               [ y ]
+ *
+ * where  z(k)  and  w(k)  both carry label  k,  0..m-1  for the
+ * strategies of the row player and  m..m+n-1  for those of the column
+ * player.  z = 0  is the artificial equilibrium.  Dropping a label,
+ * z(k)  enters, and each pivot the complement of the leaving variable
+ * enters until the missing label leaves, so the basis is complementary
+ * again.  Unlike the  LCP  tableau (-w + Mz = -q)  the rows here read
+ * w + [..]z = 1  with determinant one, and  z0  (column 0) stays zero
+ * and never enters.
+ *
+ * Degenerate games (see  degeneracy_witnesses) need no special care:
+ * the leaving variable is found by  lexminratio, which breaks ties in
+ * the min ratio test by the columns of  W  as if the right hand side
+ * were  1 + (eps, eps^2, ..)  for small  eps > 0.  The perturbed game
+ * is nondegenerate, so its bases are visited at most once and every
+ * path is finite.  The final basis is complementary and feasible for
+ * eps = 0  too, so the result is an exact equilibrium of the game.
+ */
+#[derive(Clone)]
+pub struct LemkeHowson {
+    game: Bimatrix,
+    tableau: Tableau,
+    vars: TableauVariables,
+    scale_factors: Vec<BigRational>,
+    pub pivot_count: usize,
+
+    // sign of the basis matrix, columns in row order:  Tableau  keeps  |det|
+    negative_determinant: bool,
+}
+
+impl LemkeHowson {
+
+    pub fn new(game: &Bimatrix) -> LemkeHowson {
+
+        let positive = game.positive();
+        let (m, n) = (game.m, game.n);
+        let dim = m + n;
+
+        let mut tableau = Tableau::new(dim);
+        tableau.determinant = BigInt::one();
+        let vars = TableauVariables::new(dim);
+        let mut scale_factors = vec![BigRational::one(); dim + 2];
+
+        // column of  x(i)  holds row  i  of  B  in the rows of  w(m+j),
+        // column of  y(j)  holds column  j  of  A  in the rows of  w(i)
+        for k in 0..dim {
+            let col: Vec<(usize, &BigRational)> = if k < m {
+                (0..n).map(|j| (m + j, positive.b(k, j))).collect()
+            } else {
+                (0..m).map(|i| (i, positive.a(i, k - m))).collect()
+            };
+            let scale_factor = col.iter().fold(BigInt::one(), |lcm, &(_, payoff)| lcm.lcm(payoff.denom()));
+            for (row, payoff) in col {
+                tableau.set(row, k + 1, payoff.mul(&Ratio::from_integer(scale_factor.clone())).to_integer());
+            }
+            scale_factors[k + 1] = Ratio::from_integer(scale_factor);
+        }
+        for row in 0..dim {
+            tableau.set(row, vars.rhs_col(), BigInt::one());
+        }
+
+        LemkeHowson {
+            game: game.clone(),
+            tableau,
+            vars,
+            scale_factors,
+            pivot_count: 0,
+            negative_determinant: false,
+        }
+    }
+
+    /*
+     * the tableau at the complementary basis where exactly the
+     * strategies with the labels in  support  are basic,  None  if
+     * that basis is singular
+     */
+    pub fn at_support(game: &Bimatrix, support: &[usize]) -> Option<LemkeHowson> {
+
+        let mut lemke_howson = LemkeHowson::new(game);
+        let basis: Vec<TableauVariable> = (0..lemke_howson.vars.n).map(|label| {
+            let z_k = lemke_howson.strategy(label);
+            if support.contains(&label) { z_k } else { z_k.complement() }
+        }).collect();
+
+        while let Some(row) = (0..lemke_howson.vars.n).find(|&row| !basis.contains(&lemke_howson.vars.from_row(row))) {
+            let leave = lemke_howson.vars.from_row(row);
+            let enter = basis.iter()
+                .find(|var| !lemke_howson.vars.is_basic(var) && !lemke_howson.tableau.entry(row, lemke_howson.vars.to_col(var)).is_zero())?
+                .clone();
+            lemke_howson.pivot(&leave, &enter);
+        }
+        Some(lemke_howson)
+    }
+
+    // the determinant changes sign with a negative pivot element
+    fn pivot(&mut self, leave: &TableauVariable, enter: &TableauVariable) {
+        if self.tableau.entry(self.vars.to_row(leave), self.vars.to_col(enter)).is_negative() {
+            self.negative_determinant = !self.negative_determinant;
+        }
+        self.vars.pivot(&mut self.tableau, leave, enter);
+    }
+
+    // the variable  z(k+1)  with label  k  as a strategy
+    fn strategy(&self, label: usize) -> TableauVariable {
+        if label >= self.vars.n {
+            panic!("Label {} out of range, the game has {} strategies", label, self.vars.n);
+        }
+        self.vars.z(label + 1)
+    }
+
+    /*
+     * follow the path from the current complementary basis (the
+     * artificial equilibrium at first) dropping  label
+     * returns the number of pivots
+     */
+    pub fn drop_label(&mut self, label: usize) -> usize {
+
+        let z_k = self.strategy(label);
+        let w_k = z_k.complement();
+
+        let mut enter = if self.vars.is_basic(&z_k) { w_k.clone() } else { z_k.clone() };
+        let mut pivots = 0;
+        loop {
+            let (leave, _) = lexminratio(&self.tableau, &self.vars, &enter)
+                .expect("Best response polytopes are bounded for positive payoffs");
+
+            self.pivot(&leave, &enter);
+            pivots += 1;
+
+            if leave == z_k || leave == w_k {
+                break;
+            }
+            enter = leave.complement();
+        }
+        self.pivot_count += pivots;
+        pivots
+    }
+
+    /*
+     * index of the complementary basis:  -sign det  of the basis matrix
+     * with the column of label  k  in position  k,  i.e.
+     *
+     *     -sign det [ 0      A_IJ ]  =  (-1)^(|I|+1)  sign det A_IJ  sign det B_IJ
+     *               [ B_IJ'  0    ]
+     *
+     * for supports  I  and  J  (see  classify).  The artificial
+     * equilibrium has index  -1,  Lemke-Howson from it ends at  +1.
+     * The determinant sign is tracked in  pivot,  the order of the rows
+     * adds the sign of the permutation from labels to rows.
+     */
+    pub fn index(&self) -> i32 {
+
+        let mut rows: Vec<usize> = (0..self.vars.n).map(|label| {
+            let z_k = self.strategy(label);
+            if self.vars.is_basic(&z_k) { self.vars.to_row(&z_k) } else { self.vars.to_row(&z_k.complement()) }
+        }).collect();
+
+        // sorted by transpositions
+        let mut odd = self.negative_determinant;
+        for label in 0..rows.len() {
+            while rows[label] != label {
+                let row = rows[label];
+                rows.swap(label, row);
+                odd = !odd;
+            }
+        }
+        if odd { 1 } else { -1 }
+    }
+
+    // whether the basis is at the artificial equilibrium  z = 0
+    pub fn is_artificial(&self) -> bool {
+        self.vars.solution(&self.tableau, &self.scale_factors).iter().all(|z_i| z_i.is_zero())
+    }
+
+    // the basic solution scaled to mixed strategies, with the original payoffs
+    pub fn equilibrium(&self) -> Equilibrium {
+
+        if self.is_artificial() {
+            panic!("The artificial equilibrium is not an equilibrium of the game");
+        }
+        let z = self.vars.solution(&self.tableau, &self.scale_factors);
+        let (x, y) = z.split_at(self.game.m);
+        self.game.equilibrium(normalize(x), normalize(y))
+    }
+}
+
+fn normalize(z: &[BigRational]) -> Vec<BigRational> {
+    let sum = z.iter().fold(BigRational::zero(), |acc, z_i| acc.add(z_i));
+    z.iter().map(|z_i| z_i.div(&sum)).collect()
+}
+
+/*
+ * Lemke-Howson from the artificial equilibrium with  missing_label
+ * dropped, see  LemkeHowson
+ */
+pub fn lemke_howson(game: &Bimatrix, missing_label: usize) -> Equilibrium {
+    let mut lemke_howson = LemkeHowson::new(game);
+    lemke_howson.drop_label(missing_label);
+    lemke_howson.equilibrium()
+}
+
+#[cfg(test)]
+pub fn von_stengel_3x2() -> Bimatrix {
+    Bimatrix::new(3, 2, into_bigrats(vec![3, 3, 2, 5, 0, 6]), into_bigrats(vec![3, 2, 2, 6, 3, 1]))
+}
+
+#[test]
+fn lemke_howson_finds_equilibria() {
+
+    let game = von_stengel_3x2();
+    let equilibria = [
+        (into_bigrats(vec![1, 0, 0]), into_bigrats(vec![1, 0])),
+        (vec![rat(4, 5), rat(1, 5), rat(0, 1)], vec![rat(2, 3), rat(1, 3)]),
+        (vec![rat(0, 1), rat(1, 3), rat(2, 3)], vec![rat(1, 3), rat(2, 3)]),
+    ];
+
+    for label in 0..5 {
+        let equilibrium = lemke_howson(&game, label);
+        assert!(game.is_equilibrium(&equilibrium.x, &equilibrium.y));
+        assert!(equilibria.contains(&(equilibrium.x.clone(), equilibrium.y.clone())));
+    }
+
+    let equilibrium = lemke_howson(&game, 0);
+    assert_eq!(equilibria[0], (equilibrium.x.clone(), equilibrium.y.clone()));
+    assert_eq!(rat(3, 1), equilibrium.payoff1);
+    assert_eq!(rat(3, 1), equilibrium.payoff2);
+}
+
+#[test]
+fn lemke_howson_with_rational_and_negative_payoffs() {
+
+    // matching pennies scaled by 1/3
+    let game = Bimatrix::new(2, 2,
+        vec![rat(1, 3), rat(-1, 3), rat(-1, 3), rat(1, 3)],
+        vec![rat(-1, 3), rat(1, 3), rat(1, 3), rat(-1, 3)]);
+
+    for label in 0..4 {
+        let equilibrium = lemke_howson(&game, label);
+        assert_eq!(vec![rat(1, 2), rat(1, 2)], equilibrium.x);
+        assert_eq!(vec![rat(1, 2), rat(1, 2)], equilibrium.y);
+        assert_eq!(BigRational::zero(), equilibrium.payoff1);
+    }
+}
+
+#[test]
+fn lemke_howson_on_degenerate_game() {
+
+    // column player indifferent against the first row
+    let game = Bimatrix::new(2, 2, into_bigrats(vec![1, 0, 0, 1]), into_bigrats(vec![1, 1, 0, 1]));
+    for label in 0..4 {
+        let equilibrium = lemke_howson(&game, label);
+        assert!(game.is_equilibrium(&equilibrium.x, &equilibrium.y));
+    }
+}
+
+#[test]
+fn lemke_howson_on_fully_degenerate_game() {
+
+    // all strategies are best responses to everything
+    let game = Bimatrix::new(3, 3, vec![BigRational::zero(); 9], vec![BigRational::zero(); 9]);
+    for label in 0..6 {
+        let mut lemke_howson = LemkeHowson::new(&game);
+        lemke_howson.drop_label(label);
+        let equilibrium = lemke_howson.equilibrium();
+        assert!(game.is_equilibrium(&equilibrium.x, &equilibrium.y));
+        assert!(lemke_howson.pivot_count <= 6);
+    }
+}
+
+#[test]
+fn lemke_howson_ends_at_index_one() {
+
+    let game = von_stengel_3x2();
+    assert_eq!(-1, LemkeHowson::new(&game).index());
+    for label in 0..5 {
+        let mut lemke_howson = LemkeHowson::new(&game);
+        lemke_howson.drop_label(label);
+        assert_eq!(1, lemke_howson.index());
+    }
+
+    // the mixed equilibrium with supports  {0, 1}  and  {0, 1}
+    let lemke_howson = LemkeHowson::at_support(&game, &[0, 1, 3, 4]).unwrap();
+    assert_eq!(-1, lemke_howson.index());
+}
@@ -0,0 +1,68 @@
+use num::rational::BigRational;
+use num::traits::{Zero,One};
+
+use std::ops::{Div,Mul,Neg,Sub};
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+
+/*
+ * Gaussian elimination over the rationals on the  n x n  matrix  a
+ * (row-major) with right hand side  b.  Returns the solution of
+ * a x = b  and the determinant of  a,  or None if  a  is singular.
+ */
+pub fn solve(n: usize, a: &[BigRational], b: &[BigRational]) -> Option<(Vec<BigRational>, BigRational)> {
+
+    if a.len() != n * n || b.len() != n {
+        panic!("Expected a {}x{} system but got {} entries and {} right hand sides", n, n, a.len(), b.len());
+    }
+
+    // rows of  [a | b]
+    let mut rows: Vec<Vec<BigRational>> = (0..n)
+        .map(|i| a[i*n..(i + 1)*n].iter().chain(Some(&b[i])).cloned().collect())
+        .collect();
+    let mut determinant = BigRational::one();
+
+    for col in 0..n {
+        let pivot = (col..n).find(|&row| !rows[row][col].is_zero())?;
+        if pivot != col {
+            rows.swap(pivot, col);
+            determinant = determinant.neg();
+        }
+        determinant = determinant.mul(&rows[col][col]);
+
+        let pivot_row = rows[col].clone();
+        for (row, values) in rows.iter_mut().enumerate() {
+            if row == col || values[col].is_zero() {
+                continue;
+            }
+            let factor = values[col].clone().div(&pivot_row[col]);
+            for j in col..n + 1 {
+                values[j] = values[j].clone().sub(factor.clone().mul(&pivot_row[j]));
+            }
+        }
+    }
+
+    let x = rows.iter().enumerate().map(|(i, values)| values[n].clone().div(&values[i])).collect();
+    Some((x, determinant))
+}
+
+// determinant of the  n x n  matrix  a  (row-major)
+pub fn determinant(n: usize, a: &[BigRational]) -> BigRational {
+    match solve(n, a, &vec![BigRational::zero(); n]) {
+        Some((_, determinant)) => determinant,
+        None => BigRational::zero(),
+    }
+}
+
+#[test]
+fn solve_works() {
+
+    let a = into_bigrats(vec![0, 2, 1, 1, 1, 1, 2, 0, 3]);
+    let (x, det) = solve(3, &a, &into_bigrats(vec![3, 3, 5])).unwrap();
+    assert_eq!(into_bigrats(vec![1, 1, 1]), x);
+    assert_eq!(rat(-4, 1), det);
+    assert_eq!(det, determinant(3, &a));
+
+    assert_eq!(None, solve(2, &into_bigrats(vec![1, 2, 2, 4]), &into_bigrats(vec![1, 1])));
+    assert_eq!(BigRational::zero(), determinant(2, &into_bigrats(vec![1, 2, 2, 4])));
+}
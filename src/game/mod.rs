@@ -1 +1,33 @@
 mod extensive_form;
+mod bimatrix;
+mod classification;
+mod degeneracy;
+mod dominance;
+mod equilibrium_graph;
+mod extreme_equilibria;
+mod generators;
+mod lemke_howson;
+mod linear;
+mod nfg;
+mod polymatrix;
+mod polytope;
+mod support_enumeration;
+mod symmetric;
+mod tracing;
+mod zero_sum;
+
+pub use self::bimatrix::{Bimatrix,Equilibrium};
+pub use self::classification::{Classification,classify};
+pub use self::degeneracy::{DegeneracyWitness,degeneracy_witnesses,is_degenerate};
+pub use self::dominance::{Dominance,Dominator,Elimination,Reduction,eliminate_dominated};
+pub use self::equilibrium_graph::{EquilibriumGraph,LabelEdge,lemke_howson_graph};
+pub use self::extreme_equilibria::{ExtremeEquilibria,NashSubset,extreme_equilibria};
+pub use self::generators::{GameGenerator,GameTree,InformationSet,TreeNode,hard_for_lemke_howson};
+pub use self::lemke_howson::{LemkeHowson,lemke_howson};
+pub use self::nfg::{NfgError,NfgGame};
+pub use self::polymatrix::{PolymatrixGame,PolymatrixEquilibrium};
+pub use self::polytope::{Polytope,Vertex};
+pub use self::support_enumeration::support_enumeration;
+pub use self::symmetric::SymmetricGame;
+pub use self::tracing::{tracing_lcp,tracing_path,tracing_procedure};
+pub use self::zero_sum::{ZeroSumGame,ZeroSumSolution};
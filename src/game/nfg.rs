@@ -0,0 +1,398 @@
+use num::bigint::BigInt;
+use num::rational::BigRational;
+use num::traits::{Pow,Zero};
+
+use std::str::FromStr;
+
+use super::bimatrix::Bimatrix;
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+
+/*
+ * A two-player game in Gambit's  .nfg  format with its labels.  Payoffs
+ * are listed by strategy profile, the strategy of the first player
+ * changing fastest, either directly (payoff version) or as indices
+ * into a list of outcomes (outcome version, 0 is the null outcome).
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct NfgGame {
+    pub title: String,
+    pub comment: String,
+    pub players: Vec<String>,
+    pub strategies: Vec<Vec<String>>,
+    pub game: Bimatrix,
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct NfgError {
+    pub message: String,
+}
+
+#[derive(Debug,Clone,PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Text(String),
+    Word(String),
+}
+
+impl NfgGame {
+
+    // strategies and players labeled by number as Gambit does
+    pub fn from_bimatrix(game: Bimatrix) -> NfgGame {
+        let strategies = vec![numbered(game.m), numbered(game.n)];
+        NfgGame { title: String::new(), comment: String::new(), players: numbered(2), strategies, game }
+    }
+
+    pub fn parse(text: &str) -> Result<NfgGame, NfgError> {
+
+        let mut parser = Parser { tokens: tokenize(text)?, pos: 0 };
+        parser.expect_word("NFG")?;
+        parser.expect_word("1")?;
+        let precision = parser.word()?;
+        if precision != "R" && precision != "D" {
+            return Err(error(format!("Expected R or D for the number type but found {}", precision)));
+        }
+        let title = parser.text()?;
+
+        parser.expect(Token::Open)?;
+        let mut players = Vec::new();
+        while !parser.next_is(&Token::Close) {
+            players.push(parser.text()?);
+        }
+        parser.expect(Token::Close)?;
+        if players.len() != 2 {
+            return Err(error(format!("Only two-player games are supported but found {} players", players.len())));
+        }
+
+        // counts (payoff version) or lists of strategy labels
+        parser.expect(Token::Open)?;
+        let mut strategies = Vec::new();
+        while !parser.next_is(&Token::Close) {
+            if parser.next_is(&Token::Open) {
+                parser.expect(Token::Open)?;
+                let mut labels = Vec::new();
+                while !parser.next_is(&Token::Close) {
+                    labels.push(parser.text()?);
+                }
+                parser.expect(Token::Close)?;
+                strategies.push(labels);
+            } else {
+                let count = parser.word()?;
+                let count: usize = count.parse().map_err(|_| error(format!("Expected a number of strategies but found {}", count)))?;
+                // each strategy takes part in a profile with a payoff later on
+                if count > parser.remaining() {
+                    return Err(error(format!("{} strategies are more than the file has payoffs for", count)));
+                }
+                strategies.push(numbered(count));
+            }
+        }
+        parser.expect(Token::Close)?;
+        if strategies.len() != 2 || strategies.iter().any(|labels| labels.is_empty()) {
+            return Err(error(format!("Expected strategies for 2 players but found {:?}", strategies)));
+        }
+
+        let comment = match parser.peek() {
+            Some(&Token::Text(_)) => parser.text()?,
+            _ => String::new(),
+        };
+
+        let (m, n) = (strategies[0].len(), strategies[1].len());
+        let profiles = m.checked_mul(n).filter(|&profiles| profiles <= parser.remaining())
+            .ok_or_else(|| error(format!("{}x{} strategy profiles are more than the file has payoffs for", m, n)))?;
+        let payoffs = if parser.next_is(&Token::Open) {
+            parser.outcome_payoffs(profiles)?
+        } else {
+            (0..profiles).map(|_| Ok((parser.number()?, parser.number()?))).collect::<Result<Vec<_>, _>>()?
+        };
+        if let Some(token) = parser.peek() {
+            return Err(error(format!("Expected the end of the file but found {:?}", token)));
+        }
+
+        let (mut a, mut b) = (vec![BigRational::zero(); profiles], vec![BigRational::zero(); profiles]);
+        for (profile, (a_ij, b_ij)) in payoffs.into_iter().enumerate() {
+            let (i, j) = (profile % m, profile / m);
+            a[i*n + j] = a_ij;
+            b[i*n + j] = b_ij;
+        }
+        Ok(NfgGame { title, comment, players, strategies, game: Bimatrix::new(m, n, a, b) })
+    }
+
+    // payoff version, with strategy counts if the strategies are numbered
+    pub fn to_payoff_format(&self) -> String {
+        let mut nfg = self.header();
+        let payoffs: Vec<String> = self.profiles().into_iter()
+            .map(|(i, j)| format!("{} {}", self.game.a(i, j), self.game.b(i, j)))
+            .collect();
+        nfg.push_str(&payoffs.join(" "));
+        nfg.push('\n');
+        nfg
+    }
+
+    // outcome version with one unnamed outcome per strategy profile
+    pub fn to_outcome_format(&self) -> String {
+        let mut nfg = self.header();
+        nfg.push_str("{\n");
+        for (i, j) in self.profiles() {
+            nfg.push_str(&format!("{{ \"\" {}, {} }}\n", self.game.a(i, j), self.game.b(i, j)));
+        }
+        nfg.push_str("}\n");
+        let contingencies: Vec<String> = (1..self.game.m * self.game.n + 1).map(|outcome| outcome.to_string()).collect();
+        nfg.push_str(&contingencies.join(" "));
+        nfg.push('\n');
+        nfg
+    }
+
+    fn header(&self) -> String {
+        let players: Vec<String> = self.players.iter().map(|player| quote(player)).collect();
+        let strategies: Vec<String> = if self.strategies.iter().all(|labels| *labels == numbered(labels.len())) {
+            self.strategies.iter().map(|labels| labels.len().to_string()).collect()
+        } else {
+            self.strategies.iter().map(|labels| {
+                let labels: Vec<String> = labels.iter().map(|label| quote(label)).collect();
+                format!("{{ {} }}", labels.join(" "))
+            }).collect()
+        };
+        format!("NFG 1 R {} {{ {} }} {{ {} }}\n{}\n\n", quote(&self.title), players.join(" "), strategies.join(" "), quote(&self.comment))
+    }
+
+    // strategy profiles in file order, the first player changing fastest
+    fn profiles(&self) -> Vec<(usize, usize)> {
+        (0..self.game.n).flat_map(|j| (0..self.game.m).map(move |i| (i, j))).collect()
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next_is(&self, token: &Token) -> bool {
+        self.peek() == Some(token)
+    }
+
+    // tokens not read yet
+    fn remaining(&self) -> usize {
+        self.tokens.len() - self.pos
+    }
+
+    fn next(&mut self) -> Result<Token, NfgError> {
+        let token = self.tokens.get(self.pos).cloned().ok_or_else(|| error("Unexpected end of the file".to_string()))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), NfgError> {
+        match self.next()? {
+            ref token if *token == expected => Ok(()),
+            token => Err(error(format!("Expected {:?} but found {:?}", expected, token))),
+        }
+    }
+
+    fn expect_word(&mut self, expected: &str) -> Result<(), NfgError> {
+        self.expect(Token::Word(expected.to_string()))
+    }
+
+    fn word(&mut self) -> Result<String, NfgError> {
+        match self.next()? {
+            Token::Word(word) => Ok(word),
+            token => Err(error(format!("Expected a word or number but found {:?}", token))),
+        }
+    }
+
+    fn text(&mut self) -> Result<String, NfgError> {
+        match self.next()? {
+            Token::Text(text) => Ok(text),
+            token => Err(error(format!("Expected a quoted string but found {:?}", token))),
+        }
+    }
+
+    fn number(&mut self) -> Result<BigRational, NfgError> {
+        let word = self.word()?;
+        parse_number(&word)
+    }
+
+    // outcome list and the outcome of each of the  profiles
+    fn outcome_payoffs(&mut self, profiles: usize) -> Result<Vec<(BigRational, BigRational)>, NfgError> {
+
+        let mut outcomes = vec![(BigRational::zero(), BigRational::zero())];
+        self.expect(Token::Open)?;
+        while !self.next_is(&Token::Close) {
+            self.expect(Token::Open)?;
+            self.text()?;
+            outcomes.push((self.number()?, self.number()?));
+            self.expect(Token::Close)?;
+        }
+        self.expect(Token::Close)?;
+
+        (0..profiles).map(|_| {
+            let word = self.word()?;
+            let outcome: usize = word.parse().map_err(|_| error(format!("Expected an outcome but found {}", word)))?;
+            outcomes.get(outcome).cloned().ok_or_else(|| error(format!("No outcome {} among {}", outcome, outcomes.len() - 1)))
+        }).collect()
+    }
+}
+
+// braces, quoted strings with  \"  and  \\  escapes, and words between
+// whitespace and commas
+fn tokenize(text: &str) -> Result<Vec<Token>, NfgError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => text.push(chars.next().ok_or_else(|| error("Unterminated string".to_string()))?),
+                        Some(c) => text.push(c),
+                        None => return Err(error("Unterminated string".to_string())),
+                    }
+                }
+                tokens.push(Token::Text(text));
+            },
+            c if c.is_whitespace() || c == ',' => {},
+            c => {
+                let mut word = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == ',' || c == '{' || c == '}' || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            },
+        }
+    }
+    Ok(tokens)
+}
+
+// decimal exponents beyond this would build huge integers
+const MAX_EXPONENT: u32 = 10_000;
+
+// integers, fractions  p/q  and decimals like  -1.25e2,  all exact
+fn parse_number(word: &str) -> Result<BigRational, NfgError> {
+    let invalid = || error(format!("Expected a payoff but found {}", word));
+    if word.contains('/') {
+        return BigRational::from_str(word).map_err(|_| invalid());
+    }
+    let (mantissa, exponent) = match word.find(['e', 'E']) {
+        Some(pos) => (&word[..pos], word[pos + 1..].parse::<i64>().map_err(|_| invalid())?),
+        None => (word, 0),
+    };
+    let (negative, digits) = match mantissa.chars().next() {
+        Some('-') => (true, &mantissa[1..]),
+        Some('+') => (false, &mantissa[1..]),
+        _ => (false, mantissa),
+    };
+    let (whole, fraction) = match digits.find('.') {
+        Some(pos) => (&digits[..pos], &digits[pos + 1..]),
+        None => (digits, ""),
+    };
+    if whole.is_empty() && fraction.is_empty() || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    if exponent.unsigned_abs() > MAX_EXPONENT as u64 {
+        return Err(error(format!("The exponent of {} is beyond {}", word, MAX_EXPONENT)));
+    }
+
+    let numer = BigInt::from_str(&format!("0{}{}", whole, fraction)).map_err(|_| invalid())?;
+    let shift = exponent - fraction.len() as i64;
+    let power = BigRational::from_integer(BigInt::from(10).pow(shift.unsigned_abs()));
+    let value = BigRational::from_integer(numer);
+    let value = if shift < 0 { value / power } else { value * power };
+    Ok(if negative { -value } else { value })
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn numbered(count: usize) -> Vec<String> {
+    (1..=count).map(|k| k.to_string()).collect()
+}
+
+fn error(message: String) -> NfgError {
+    NfgError { message }
+}
+
+#[test]
+fn nfg_reads_payoff_version() {
+
+    let nfg = NfgGame::parse("NFG 1 R \"Selten (IJGT, 75), Figure 2, normal form\" { \"Player 1\" \"Player 2\" } { 3 2 }\n\n1 1 0 2 0 2 1 1 0 3 2 0\n").unwrap();
+
+    assert_eq!("Selten (IJGT, 75), Figure 2, normal form", nfg.title);
+    assert_eq!(vec!["Player 1".to_string(), "Player 2".to_string()], nfg.players);
+    assert_eq!(vec![numbered(3), numbered(2)], nfg.strategies);
+    assert_eq!(Bimatrix::new(3, 2, into_bigrats(vec![1, 1, 0, 0, 0, 2]), into_bigrats(vec![1, 1, 2, 3, 2, 0])), nfg.game);
+
+    assert_eq!(nfg, NfgGame::parse(&nfg.to_payoff_format()).unwrap());
+    assert_eq!(nfg, NfgGame::parse(&nfg.to_outcome_format()).unwrap());
+}
+
+#[test]
+fn nfg_reads_outcome_version() {
+
+    let nfg = NfgGame::parse(r#"NFG 1 R "Matching \"pennies\"" { "Row" "Column" }
+
+{ { "Heads" "Tails" }
+{ "heads" "tails" "both" }
+}
+"a comment"
+
+{
+{ "win" 1, -1 }
+{ "lose" -1.5, 3/2 }
+{ "" 1e-1 -0.1 }
+}
+1 2 2 1 3 0
+"#).unwrap();
+
+    assert_eq!("Matching \"pennies\"", nfg.title);
+    assert_eq!("a comment", nfg.comment);
+    assert_eq!(vec!["Heads".to_string(), "Tails".to_string()], nfg.strategies[0]);
+    assert_eq!(vec![rat(1, 1), rat(-3, 2), rat(1, 10), rat(-3, 2), rat(1, 1), rat(0, 1)], nfg.game.a);
+    assert_eq!(vec![rat(-1, 1), rat(3, 2), rat(-1, 10), rat(3, 2), rat(-1, 1), rat(0, 1)], nfg.game.b);
+
+    assert_eq!(nfg, NfgGame::parse(&nfg.to_outcome_format()).unwrap());
+    assert_eq!(nfg, NfgGame::parse(&nfg.to_payoff_format()).unwrap());
+}
+
+#[test]
+fn nfg_rejects_malformed_files() {
+
+    for text in [
+        "NFG 1 R \"three\" { \"1\" \"2\" \"3\" } { 1 1 1 }\n1 1 1\n",
+        "NFG 1 R \"short\" { \"1\" \"2\" } { 2 2 }\n1 1 0 2 0 2\n",
+        "NFG 1 R \"bad payoff\" { \"1\" \"2\" } { 1 1 }\n1 x\n",
+        "NFG 1 R \"no outcome\" { \"1\" \"2\" } { 1 1 }\n{ { \"\" 1 1 } }\n2\n",
+        "NFG 1 R \"unterminated { \"1\" \"2\" } { 1 1 }\n1 1\n",
+        "NFG 1 R \"huge\" { \"1\" \"2\" } { 1 1 }\n1e2000000000 1\n",
+        "NFG 1 R \"tiny\" { \"1\" \"2\" } { 1 1 }\n1 -2.5E-10001\n",
+    ] {
+        assert!(NfgGame::parse(text).is_err(), "{}", text);
+    }
+    let huge = NfgGame::parse("NFG 1 R \"\" { \"1\" \"2\" } { 1 1 }\n1e2000000000 1\n").unwrap_err();
+    assert_eq!("The exponent of 1e2000000000 is beyond 10000", huge.message);
+
+    // strategy counts are checked against the payoffs before anything is allocated
+    let many = NfgGame::parse("NFG 1 R \"\" { \"1\" \"2\" } { 4000000000 2 }\n1 1\n").unwrap_err();
+    assert_eq!("4000000000 strategies are more than the file has payoffs for", many.message);
+    let most = NfgGame::parse(&format!("NFG 1 R \"\" {{ \"1\" \"2\" }} {{ {} 2 }}\n1 1\n", usize::MAX)).unwrap_err();
+    assert_eq!(format!("{} strategies are more than the file has payoffs for", usize::MAX), most.message);
+    let profiles = NfgGame::parse("NFG 1 R \"\" { \"1\" \"2\" } { 3 3 }\n1 1 1 1\n").unwrap_err();
+    assert_eq!("3x3 strategy profiles are more than the file has payoffs for", profiles.message);
+
+    let nfg = NfgGame::from_bimatrix(Bimatrix::new(1, 1, into_bigrats(vec![1]), into_bigrats(vec![2])));
+    assert_eq!("NFG 1 R \"\" { \"1\" \"2\" } { 1 1 }\n\"\"\n\n1 2\n", nfg.to_payoff_format());
+}
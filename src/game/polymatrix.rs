@@ -0,0 +1,180 @@
+use num::rational::BigRational;
+use num::traits::{Zero,One};
+
+use std::ops::{Add,Mul};
+
+use lemke::LCP;
+
+use super::bimatrix::{Bimatrix,costs};
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+
+/*
+ * N-player game where each edge  {p, q}  of a graph is a bimatrix game
+ * between  p  (rows) and  q  (columns), played with the same mixed
+ * strategy on all edges.  Each player gets the sum of the edge payoffs.
+ */
+#[derive(Debug,Clone)]
+pub struct PolymatrixGame {
+    pub strategies: Vec<usize>,
+    pub edges: Vec<(usize, usize, Bimatrix)>,
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct PolymatrixEquilibrium {
+    pub strategies: Vec<Vec<BigRational>>,
+    pub payoffs: Vec<BigRational>,
+}
+
+impl PolymatrixGame {
+
+    // players  0..strategies.len()  with that many pure strategies each
+    pub fn new(strategies: Vec<usize>) -> PolymatrixGame {
+        if strategies.contains(&0) {
+            panic!("Each player needs at least one strategy: {:?}", strategies);
+        }
+        PolymatrixGame { strategies, edges: Vec::new() }
+    }
+
+    pub fn add_edge(&mut self, p: usize, q: usize, game: Bimatrix) {
+        if p == q || p >= self.strategies.len() || q >= self.strategies.len() {
+            panic!("No edge between players {} and {} among {}", p, q, self.strategies.len());
+        }
+        if game.m != self.strategies[p] || game.n != self.strategies[q] {
+            panic!("The game between {} and {} must be {}x{} but is {}x{}", p, q, self.strategies[p], self.strategies[q], game.m, game.n);
+        }
+        self.edges.push((p, q, game));
+    }
+
+    /*
+     * payoff  payoff(p, i, q, j)  to  p  playing  i  against  q  playing  j
+     * summed over the edges between  p  and  q
+     */
+    fn payoff(&self, p: usize, i: usize, q: usize, j: usize) -> BigRational {
+        self.edges.iter().fold(BigRational::zero(), |acc, &(from, to, ref game)| {
+            if from == p && to == q {
+                acc.add(game.a(i, j))
+            } else if from == q && to == p {
+                acc.add(game.b(j, i))
+            } else {
+                acc
+            }
+        })
+    }
+
+    // payoffs of the pure strategies of  p  against the others' mixed strategies
+    pub fn strategy_payoffs(&self, p: usize, profile: &[Vec<BigRational>]) -> Vec<BigRational> {
+        (0..self.strategies[p]).map(|i| {
+            (0..self.strategies.len()).filter(|&q| q != p).fold(BigRational::zero(), |acc, q| {
+                (0..self.strategies[q]).fold(acc, |acc, j| acc.add(self.payoff(p, i, q, j).mul(&profile[q][j])))
+            })
+        }).collect()
+    }
+
+    pub fn payoffs(&self, profile: &[Vec<BigRational>]) -> Vec<BigRational> {
+        (0..self.strategies.len()).map(|p| {
+            self.strategy_payoffs(p, profile).iter().zip(profile[p].iter())
+                .fold(BigRational::zero(), |acc, (payoff, x)| acc.add(payoff.mul(x)))
+        }).collect()
+    }
+
+    pub fn is_equilibrium(&self, profile: &[Vec<BigRational>]) -> bool {
+        (0..self.strategies.len()).all(|p| {
+            let payoffs = self.strategy_payoffs(p, profile);
+            let best = payoffs.iter().max().unwrap();
+            profile[p].iter().zip(payoffs.iter()).all(|(x, payoff)| x.is_zero() || payoff == best)
+        })
+    }
+
+    /*
+     * With the  costs  C[p][q]  of the payoffs  A[p][q]  for  q != p  and
+     * the all-ones  C[p][p],  which only add constants to the costs of  p,
+     * the equilibria are the solutions of the LCP
+     *
+     *     w = [ C  -E' ] [ x ] + [  0 ] >= 0,   [ x ] >= 0,   w'z = 0
+     *         [ E   0  ] [ u ]   [ -1 ]         [ u ]
+     *
+     * where row  p  of  E  sums the strategies of  p,  so Lemke with
+     * covering vector 1 ends in a solution (the LCP is feasible).  There
+     * u(p)  is the least cost of  p  and  1'x(p) = 1.
+     */
+    pub fn solve(&self) -> PolymatrixEquilibrium {
+
+        let players = self.strategies.len();
+        let offsets: Vec<usize> = self.strategies.iter().scan(0, |offset, &n| { *offset += n; Some(*offset - n) }).collect();
+        let total: usize = self.strategies.iter().sum();
+        let size = total + players;
+
+        // costs[p][q]  row-major over the strategies of  p  and  q
+        let costs: Vec<Vec<Vec<BigRational>>> = (0..players).map(|p| (0..players).map(|q| {
+            if p == q {
+                return vec![BigRational::one(); self.strategies[p] * self.strategies[q]];
+            }
+            let payoffs: Vec<BigRational> = (0..self.strategies[p]).flat_map(|i| (0..self.strategies[q]).map(move |j| (i, j)))
+                .map(|(i, j)| self.payoff(p, i, q, j))
+                .collect();
+            costs(&payoffs)
+        }).collect()).collect();
+
+        let mut m = vec![BigRational::zero(); size * size];
+        for p in 0..players {
+            for i in 0..self.strategies[p] {
+                let row = offsets[p] + i;
+                for q in 0..players {
+                    for j in 0..self.strategies[q] {
+                        m[row*size + offsets[q] + j] = costs[p][q][i*self.strategies[q] + j].clone();
+                    }
+                }
+                m[row*size + total + p] = -BigRational::one();
+                m[(total + p)*size + row] = BigRational::one();
+            }
+        }
+
+        let mut q = vec![BigRational::zero(); total];
+        q.extend(vec![-BigRational::one(); players]);
+
+        let mut names: Vec<String> = (0..players).flat_map(|p| (0..self.strategies[p]).map(move |i| format!("x{}:{}", p, i))).collect();
+        names.extend((0..players).map(|p| format!("u{}", p)));
+
+        let mut lcp = LCP::with_names(m, q, names);
+        lcp.add_covering_vector(vec![BigRational::one(); size]);
+        let z = lcp.solve(0).expect("Lemke solves the LCP of a polymatrix game with positive costs");
+
+        let strategies: Vec<Vec<BigRational>> = (0..players).map(|p| z[offsets[p]..offsets[p] + self.strategies[p]].to_vec()).collect();
+        let payoffs = self.payoffs(&strategies);
+        PolymatrixEquilibrium { strategies, payoffs }
+    }
+}
+
+#[test]
+fn polymatrix_with_two_players_is_bimatrix() {
+
+    let mut game = PolymatrixGame::new(vec![2, 2]);
+    game.add_edge(0, 1, Bimatrix::new(2, 2, into_bigrats(vec![1, -1, -1, 1]), into_bigrats(vec![-1, 1, 1, -1])));
+    let equilibrium = game.solve();
+
+    assert_eq!(vec![vec![rat(1, 2), rat(1, 2)], vec![rat(1, 2), rat(1, 2)]], equilibrium.strategies);
+    assert_eq!(vec![BigRational::zero(), BigRational::zero()], equilibrium.payoffs);
+}
+
+#[test]
+fn polymatrix_network_equilibrium() {
+
+    // a ring of 12 players, each with 3 strategies, playing coordination
+    // games with the next and zero-sum games with the one after
+    let players = 12;
+    let mut game = PolymatrixGame::new(vec![3; players]);
+    for p in 0..players {
+        let coordination = into_bigrats(vec![2, 0, 0, 0, 1, 0, 0, 0, 3]);
+        let payoffs = into_bigrats((0..9).map(|e| ((e * 7 + p as i64 * 5) % 11) - 5).collect());
+        game.add_edge(p, (p + 1) % players, Bimatrix::new(3, 3, coordination.clone(), coordination));
+        game.add_edge(p, (p + 2) % players, Bimatrix::new(3, 3, payoffs.clone(), payoffs.iter().map(|a| -a).collect()));
+    }
+
+    let equilibrium = game.solve();
+    assert!(game.is_equilibrium(&equilibrium.strategies));
+    for x in equilibrium.strategies.iter() {
+        assert_eq!(BigRational::one(), x.iter().fold(BigRational::zero(), |acc, x_i| acc.add(x_i)));
+    }
+    assert_eq!(game.payoffs(&equilibrium.strategies), equilibrium.payoffs);
+}
@@ -0,0 +1,327 @@
+use num::bigint::BigInt;
+use num::integer::Integer;
+use num::rational::{Ratio,BigRational};
+use num::traits::{Zero,One,Signed};
+
+use std::cmp::Ordering;
+use std::ops::{Add,Div,Mul};
+
+use lemke::Tableau;
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+#[cfg(test)] use super::generators::GameGenerator;
+#[cfg(test)] use super::linear::solve;
+
+/*
+ * vertex of  { u >= 0 : Cu <= 1 }  with the coordinates  zeros  where
+ * u(j) = 0  and the rows  tight  where  (Cu)(r) = 1
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct Vertex {
+    pub point: Vec<BigRational>,
+    pub zeros: Vec<usize>,
+    pub tight: Vec<usize>,
+}
+
+/*
+ * the polytope  { u >= 0 : Cu <= 1 }  for the  k x d  matrix  C
+ * (row-major), bounded if  C  is positive as for best response
+ * polytopes, see  Bimatrix::positive
+ */
+pub struct Polytope {
+    pub k: usize,
+    pub d: usize,
+    pub c: Vec<BigRational>,
+}
+
+/*
+ * a feasible basis: the tableau rows read  det s(r) + sum C'[r][j] u(j) = rhs
+ * for the basic variables; variables  0..d-1  are  u,  d..d+k-1  the slacks.
+ * Row  k  is the objective  det z + sum u(j) = 0,  maximized at  u = 0.
+ */
+struct Basis {
+    tableau: Tableau,
+    basic: Vec<usize>,
+    cobasic: Vec<usize>,
+}
+
+impl Polytope {
+
+    pub fn new(k: usize, d: usize, c: Vec<BigRational>) -> Polytope {
+        if c.len() != k * d {
+            panic!("C must be {}x{} but has {} entries", k, d, c.len());
+        }
+        Polytope { k, d, c }
+    }
+
+    /*
+     * all vertices, by the reverse search of Avis and Fukuda as in lrs.
+     * The simplex method maximizing  z = -sum u(j)  from any feasible
+     * basis, entering the least variable that improves  z  and leaving
+     * by the lexicographic ratio test (see  lex_min_ratio_row),  ends at
+     * the basis  u = 0.  Its pivots form a tree on the lexicographically
+     * feasible bases, searched depth first by undoing them; going back up
+     * is a simplex pivot, so nothing but the current tableau is stored.
+     * A vertex of several bases (when the polytope is degenerate) is
+     * reported only at its lex-min basis.
+     */
+    pub fn vertices(&self) -> Vec<Vertex> {
+
+        let (mut basis, scale_factors) = self.slack_basis();
+        let mut vertices = vec![self.vertex(&basis, &scale_factors)];
+
+        let mut depth = 0;
+        let mut col = 0;
+        loop {
+            while col < self.d {
+                match self.reverse_pivot_row(&basis, col) {
+                    Some(row) => {
+                        self.pivot(&mut basis, row, col);
+                        depth += 1;
+                        col = 0;
+                        if self.is_lex_min(&basis) {
+                            vertices.push(self.vertex(&basis, &scale_factors));
+                        }
+                    },
+                    None => col += 1,
+                }
+            }
+            if depth == 0 {
+                break;
+            }
+            // back to the parent, then on with the next column there
+            let (row, parent_col) = self.simplex_pivot(&basis);
+            self.pivot(&mut basis, row, parent_col);
+            depth -= 1;
+            col = parent_col + 1;
+        }
+
+        vertices
+    }
+
+    // the tableau at  u = 0  with the columns of  C  scaled to integers
+    fn slack_basis(&self) -> (Basis, Vec<BigRational>) {
+
+        let mut tableau = Tableau::with_dimensions(self.k + 1, self.d + 1);
+        let mut scale_factors = Vec::with_capacity(self.d);
+        for j in 0..self.d {
+            let scale_factor = (0..self.k).fold(BigInt::one(), |lcm, r| lcm.lcm(self.c[r*self.d + j].denom()));
+            for r in 0..self.k {
+                tableau.set(r, j, self.c[r*self.d + j].clone().mul(&Ratio::from_integer(scale_factor.clone())).to_integer());
+            }
+            tableau.set(self.k, j, BigInt::one());
+            scale_factors.push(Ratio::from_integer(scale_factor));
+        }
+        for r in 0..self.k {
+            tableau.set(r, self.d, BigInt::one());
+        }
+
+        let basis = Basis {
+            tableau,
+            basic: (self.d..self.d + self.k).collect(),
+            cobasic: (0..self.d).collect(),
+        };
+        (basis, scale_factors)
+    }
+
+    fn pivot(&self, basis: &mut Basis, row: usize, col: usize) {
+        basis.tableau.pivot(row, col);
+        ::std::mem::swap(&mut basis.basic[row], &mut basis.cobasic[col]);
+    }
+
+    /*
+     * the parent pivot: the least cobasic variable with negative
+     * objective coefficient enters, so that  z  increases
+     */
+    fn simplex_pivot(&self, basis: &Basis) -> (usize, usize) {
+        let col = (0..self.d)
+            .filter(|&col| basis.tableau.entry(self.k, col).is_negative())
+            .min_by_key(|&col| basis.cobasic[col])
+            .expect("Only the basis at u = 0 has no improving column");
+        let row = self.lex_min_ratio_row(basis, col).expect("The polytope is bounded");
+        (row, col)
+    }
+
+    /*
+     * the row of the pivot bringing in the variable in  col  whose
+     * simplex pivot leads back to  basis,  if any.  Afterwards the
+     * leaving variable (into  col) must be the least with negative
+     * objective coefficient; the ratio test there picks the row again.
+     */
+    fn reverse_pivot_row(&self, basis: &Basis, col: usize) -> Option<usize> {
+
+        let tableau = &basis.tableau;
+        let objective = tableau.entry(self.k, col);
+        if !objective.is_positive() {
+            return None;
+        }
+        let row = self.lex_min_ratio_row(basis, col)?;
+        let leaving = basis.basic[row];
+        let pivot = tableau.entry(row, col);
+
+        // signs of the objective coefficients after the pivot
+        let improves = (0..self.d).any(|other| {
+            other != col && basis.cobasic[other] < leaving && {
+                let coefficient = tableau.entry(self.k, other).mul(pivot) - objective.mul(tableau.entry(row, other));
+                coefficient.is_negative()
+            }
+        });
+        if improves { None } else { Some(row) }
+    }
+
+    /*
+     * the row with positive entry in  col  of least ratio of the right
+     * hand side to the entry, where the constraint of variable  i  (the
+     * slack, or  u(i) >= 0  itself) is perturbed by  eps^(i+1)
+     */
+    fn lex_min_ratio_row(&self, basis: &Basis, col: usize) -> Option<usize> {
+
+        let tableau = &basis.tableau;
+        let mut rows: Vec<usize> = (0..self.k).filter(|&row| tableau.entry(row, col).is_positive()).collect();
+        if rows.is_empty() {
+            return None;
+        }
+        rows = least_ratios(tableau, rows, col, |row| tableau.entry(row, self.d).clone());
+
+        for var in 0..self.d + self.k {
+            if rows.len() == 1 {
+                break;
+            }
+            // coefficient of  var  in each row, det in its row if basic
+            let basic_row = basis.basic.iter().position(|&basic| basic == var);
+            let cobasic_col = basis.cobasic.iter().position(|&cobasic| cobasic == var);
+            rows = least_ratios(tableau, rows, col, |row| match (basic_row, cobasic_col) {
+                (Some(basic_row), _) if basic_row == row => tableau.determinant.clone(),
+                (_, Some(cobasic_col)) => tableau.entry(row, cobasic_col).clone(),
+                _ => BigInt::zero(),
+            });
+        }
+        rows.first().cloned()
+    }
+
+    /*
+     * whether no degenerate pivot, a basic variable of value 0 leaving
+     * for a lesser cobasic variable, gives another basis of the vertex
+     */
+    fn is_lex_min(&self, basis: &Basis) -> bool {
+        (0..self.k).filter(|&row| basis.tableau.entry(row, self.d).is_zero()).all(|row| {
+            (0..self.d).all(|col| basis.cobasic[col] > basis.basic[row] || basis.tableau.entry(row, col).is_zero())
+        })
+    }
+
+    fn vertex(&self, basis: &Basis, scale_factors: &[BigRational]) -> Vertex {
+
+        let det = Ratio::from_integer(basis.tableau.determinant.clone());
+        let mut point = vec![BigRational::zero(); self.d];
+        for (row, &var) in basis.basic.iter().enumerate() {
+            if var < self.d {
+                let value = Ratio::from_integer(basis.tableau.entry(row, self.d).clone()).div(&det);
+                point[var] = value.mul(&scale_factors[var]);
+            }
+        }
+
+        let zeros = (0..self.d).filter(|&j| point[j].is_zero()).collect();
+        let tight = (0..self.k).filter(|&r| {
+            let lhs = (0..self.d).fold(BigRational::zero(), |acc, j| acc.add(self.c[r*self.d + j].clone().mul(&point[j])));
+            lhs.is_one()
+        }).collect();
+
+        Vertex { point, zeros, tight }
+    }
+}
+
+// the  rows  of least  value(row) / entry(row, col),  all entries positive
+fn least_ratios<F>(tableau: &Tableau, rows: Vec<usize>, col: usize, value: F) -> Vec<usize> where F: Fn(usize) -> BigInt {
+    let mut least: Vec<usize> = Vec::new();
+    let mut least_value = BigInt::zero();
+    for row in rows {
+        let row_value = value(row);
+        let order = match least.first() {
+            None => Ordering::Less,
+            Some(&min) => row_value.clone().mul(tableau.entry(min, col)).cmp(&least_value.clone().mul(tableau.entry(row, col))),
+        };
+        match order {
+            Ordering::Less => {
+                least = vec![row];
+                least_value = row_value;
+            },
+            Ordering::Equal => least.push(row),
+            Ordering::Greater => {},
+        }
+    }
+    least
+}
+
+#[test]
+fn polytope_finds_vertices() {
+
+    // the unit square  u1, u2 <= 1  cut by  u1 + u2 <= 3/2
+    let polytope = Polytope::new(3, 2, vec![rat(1, 1), rat(0, 1), rat(0, 1), rat(1, 1), rat(2, 3), rat(2, 3)]);
+    let points: Vec<Vec<BigRational>> = polytope.vertices().into_iter().map(|vertex| vertex.point).collect();
+
+    assert_eq!(5, points.len());
+    for point in [
+        into_bigrats(vec![0, 0]),
+        into_bigrats(vec![1, 0]),
+        into_bigrats(vec![0, 1]),
+        vec![rat(1, 1), rat(1, 2)],
+        vec![rat(1, 2), rat(1, 1)],
+    ] {
+        assert!(points.contains(&point));
+    }
+}
+
+#[test]
+fn polytope_finds_degenerate_vertices() {
+
+    // the apex  (0, 1, 0)  lies on four facets
+    let polytope = Polytope::new(2, 3, into_bigrats(vec![1, 1, 0, 0, 1, 1]));
+    let vertices = polytope.vertices();
+    let apex = vertices.iter().find(|vertex| vertex.point == into_bigrats(vec![0, 1, 0])).unwrap();
+
+    assert_eq!(vec![0, 2], apex.zeros);
+    assert_eq!(vec![0, 1], apex.tight);
+    assert_eq!(5, vertices.len());
+}
+
+#[test]
+fn polytope_vertices_match_brute_force() {
+
+    let mut generator = GameGenerator::new(3);
+    for (k, d) in [(3, 3), (4, 3), (5, 4), (4, 4), (6, 3)] {
+        for _ in 0..5 {
+            // small payoff ranges make many vertices degenerate
+            let game = generator.degenerate(k, d, 0, 2).positive();
+            let polytope = Polytope::new(k, d, game.a.clone());
+            let points: Vec<Vec<BigRational>> = polytope.vertices().into_iter().map(|vertex| vertex.point).collect();
+
+            // every  d  of the  d + k  facets tight with a feasible solution
+            let mut expected: Vec<Vec<BigRational>> = Vec::new();
+            for facets in (0..1usize << (d + k)).filter(|facets| facets.count_ones() as usize == d) {
+                let mut a = Vec::new();
+                let mut b = Vec::new();
+                for facet in (0..d + k).filter(|facet| facets & (1 << facet) != 0) {
+                    if facet < d {
+                        a.extend((0..d).map(|j| if j == facet { BigRational::one() } else { BigRational::zero() }));
+                        b.push(BigRational::zero());
+                    } else {
+                        a.extend(polytope.c[(facet - d)*d..(facet - d + 1)*d].iter().cloned());
+                        b.push(BigRational::one());
+                    }
+                }
+                if let Some((point, _)) = solve(d, &a, &b) {
+                    let feasible = point.iter().all(|u| !u.is_negative()) && (0..k).all(|r| {
+                        (0..d).fold(BigRational::zero(), |acc, j| acc.add(polytope.c[r*d + j].clone().mul(&point[j]))) <= BigRational::one()
+                    });
+                    if feasible && !expected.contains(&point) {
+                        expected.push(point);
+                    }
+                }
+            }
+
+            assert_eq!(expected.len(), points.len());
+            assert!(expected.iter().all(|point| points.contains(point)));
+        }
+    }
+}
+
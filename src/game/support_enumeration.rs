@@ -0,0 +1,124 @@
+use num::rational::BigRational;
+use num::traits::{Zero,One,Signed};
+
+use super::bimatrix::{Bimatrix,Equilibrium};
+use super::linear::solve;
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+#[cfg(test)] use super::lemke_howson::{lemke_howson,von_stengel_3x2};
+
+/*
+ * All equilibria of a nondegenerate bimatrix game by support
+ * enumeration: for every pair of supports  I, J  of equal size  k
+ * solve the indifference systems
+ *
+ *     sum_{j in J} A[i][j] y(j) = u  (i in I),   sum_{j in J} y(j) = 1
+ *     sum_{i in I} B[i][j] x(i) = v  (j in J),   sum_{i in I} x(i) = 1
+ *
+ * exactly and keep the solutions that are nonnegative with only best
+ * responses in the supports, so every result is an equilibrium.  In a
+ * degenerate game (see  degeneracy_witnesses)  the systems of some
+ * supports are singular, and equilibria only found from such supports
+ * are missed (see  extreme_equilibria  instead).
+ * The number of support pairs grows exponentially with  m  and  n.
+ */
+pub fn support_enumeration(game: &Bimatrix) -> Vec<Equilibrium> {
+
+    let mut equilibria: Vec<Equilibrium> = Vec::new();
+    for k in 1..game.m.min(game.n) + 1 {
+        for rows in subsets(game.m, k) {
+            for cols in subsets(game.n, k) {
+                let y = match indifferent(&rows, &cols, game.n, |i, j| game.a(i, j)) {
+                    Some(y) => y,
+                    None => continue,
+                };
+                let x = match indifferent(&cols, &rows, game.m, |j, i| game.b(i, j)) {
+                    Some(x) => x,
+                    None => continue,
+                };
+                if game.is_equilibrium(&x, &y) {
+                    let equilibrium = game.equilibrium(x, y);
+                    if !equilibria.contains(&equilibrium) {
+                        equilibria.push(equilibrium);
+                    }
+                }
+            }
+        }
+    }
+    equilibria
+}
+
+/*
+ * the mixed strategy on  support  (out of  len  strategies) that makes
+ * the opponent indifferent between the strategies of  against, where
+ * payoff(i, j)  is the opponent's payoff for  i in against,  j in support
+ */
+fn indifferent<'a, F>(against: &[usize], support: &[usize], len: usize, payoff: F) -> Option<Vec<BigRational>> where F: Fn(usize, usize) -> &'a BigRational {
+
+    let k = support.len();
+    let mut a = Vec::with_capacity((k + 1) * (k + 1));
+    for &i in against {
+        a.extend(support.iter().map(|&j| payoff(i, j).clone()));
+        a.push(-BigRational::one());
+    }
+    a.extend((0..k).map(|_| BigRational::one()));
+    a.push(BigRational::zero());
+
+    let mut b = vec![BigRational::zero(); k];
+    b.push(BigRational::one());
+
+    let (solution, _) = solve(k + 1, &a, &b)?;
+    if solution[..k].iter().any(|p| p.is_negative()) {
+        return None;
+    }
+    let mut strategy = vec![BigRational::zero(); len];
+    for (&j, p) in support.iter().zip(solution) {
+        strategy[j] = p;
+    }
+    Some(strategy)
+}
+
+// the  k-element subsets of  0..n  in lexicographic order
+fn subsets(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    (k - 1..n).flat_map(|last| {
+        subsets(last, k - 1).into_iter().map(move |mut subset| {
+            subset.push(last);
+            subset
+        })
+    }).collect()
+}
+
+#[test]
+fn subsets_works() {
+    assert_eq!(vec![vec![0, 1], vec![0, 2], vec![1, 2]], subsets(3, 2));
+    assert_eq!(4, subsets(4, 3).len());
+}
+
+#[test]
+fn support_enumeration_finds_all_equilibria() {
+
+    let game = von_stengel_3x2();
+    let equilibria = support_enumeration(&game);
+
+    assert_eq!(3, equilibria.len());
+    assert!(equilibria.iter().any(|equilibrium| equilibrium.y == vec![rat(1, 3), rat(2, 3)] && equilibrium.payoff1 == rat(4, 1)));
+
+    // cross-check Lemke-Howson
+    for label in 0..5 {
+        assert!(equilibria.contains(&lemke_howson(&game, label)));
+    }
+}
+
+#[test]
+fn support_enumeration_finds_mixed_equilibrium() {
+
+    // battle of the sexes
+    let game = Bimatrix::new(2, 2, into_bigrats(vec![2, 0, 0, 1]), into_bigrats(vec![1, 0, 0, 2]));
+    let equilibria = support_enumeration(&game);
+
+    assert_eq!(3, equilibria.len());
+    assert!(equilibria.iter().any(|equilibrium| equilibrium.x == vec![rat(2, 3), rat(1, 3)] && equilibrium.y == vec![rat(1, 3), rat(2, 3)]));
+}
@@ -0,0 +1,100 @@
+use num::rational::BigRational;
+use num::traits::{Zero,One};
+
+use std::ops::{Add,Div};
+
+use lemke::lemke;
+
+use super::bimatrix::{Bimatrix,Equilibrium,costs};
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+
+/*
+ * Symmetric two-player game: both players have the  n x n  payoff
+ * matrix  A  (row-major) for their own strategy as row, i.e.  B = A'
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct SymmetricGame {
+    pub n: usize,
+    pub a: Vec<BigRational>,
+}
+
+impl SymmetricGame {
+
+    pub fn new(n: usize, a: Vec<BigRational>) -> SymmetricGame {
+        if n == 0 {
+            panic!("Each player needs at least one strategy");
+        }
+        if a.len() != n * n {
+            panic!("A must be {}x{} but has {} entries", n, n, a.len());
+        }
+        SymmetricGame { n, a }
+    }
+
+    // the symmetric game of a bimatrix game with  B = A',  if it is one
+    pub fn from_bimatrix(game: &Bimatrix) -> Option<SymmetricGame> {
+        if game.m != game.n || (0..game.m).any(|i| (0..game.n).any(|j| game.b(i, j) != game.a(j, i))) {
+            return None;
+        }
+        Some(SymmetricGame::new(game.n, game.a.clone()))
+    }
+
+    pub fn to_bimatrix(&self) -> Bimatrix {
+        let b = (0..self.n).flat_map(|i| (0..self.n).map(move |j| (i, j))).map(|(i, j)| self.a[j*self.n + i].clone()).collect();
+        Bimatrix::new(self.n, self.n, self.a.clone(), b)
+    }
+
+    /*
+     * A symmetric equilibrium  (x, x)  from the single LCP
+     *
+     *     w = Cz - 1 >= 0,   z >= 0,   z'w = 0
+     *
+     * with the  costs  C  of  A:  z != 0,  and the strategies with
+     * z(i) > 0  have least cost  (Cz)(i) = 1  against  z,  so  x = z / 1'z
+     * is a best response to itself.  Lemke with covering vector 1 finds  z.
+     */
+    pub fn solve(&self) -> Equilibrium {
+
+        let z = lemke(costs(&self.a), vec![-BigRational::one(); self.n], vec![BigRational::one(); self.n])
+            .expect("Lemke solves the LCP of a symmetric game with positive costs");
+
+        let sum = z.iter().fold(BigRational::zero(), |acc, z_i| acc.add(z_i));
+        let x: Vec<BigRational> = z.iter().map(|z_i| z_i.div(&sum)).collect();
+        self.to_bimatrix().equilibrium(x.clone(), x)
+    }
+}
+
+#[test]
+fn symmetric_solves_rock_paper_scissors() {
+
+    let game = SymmetricGame::new(3, into_bigrats(vec![0, -1, 1, 1, 0, -1, -1, 1, 0]));
+    let equilibrium = game.solve();
+
+    assert_eq!(vec![rat(1, 3); 3], equilibrium.x);
+    assert_eq!(equilibrium.x, equilibrium.y);
+    assert_eq!(BigRational::zero(), equilibrium.payoff1);
+}
+
+#[test]
+fn symmetric_solves_hawk_dove() {
+
+    let game = SymmetricGame::new(2, into_bigrats(vec![0, 3, 1, 2]));
+    let equilibrium = game.solve();
+
+    assert_eq!(vec![rat(1, 2), rat(1, 2)], equilibrium.x);
+    assert!(game.to_bimatrix().is_equilibrium(&equilibrium.x, &equilibrium.y));
+    assert_eq!(Some(game.clone()), SymmetricGame::from_bimatrix(&game.to_bimatrix()));
+}
+
+#[test]
+fn symmetric_finds_symmetric_equilibrium_of_coordination_game() {
+
+    let game = SymmetricGame::new(3, into_bigrats(vec![2, 0, 0, 0, 1, 0, 0, 0, 3]));
+    let equilibrium = game.solve();
+
+    assert_eq!(equilibrium.x, equilibrium.y);
+    assert!(game.to_bimatrix().is_equilibrium(&equilibrium.x, &equilibrium.y));
+
+    let asymmetric = Bimatrix::new(2, 2, into_bigrats(vec![1, 0, 0, 1]), into_bigrats(vec![0, 1, 1, 0]));
+    assert_eq!(None, SymmetricGame::from_bimatrix(&asymmetric));
+}
@@ -0,0 +1,130 @@
+use num::rational::BigRational;
+use num::traits::{Zero,One,Signed};
+
+use std::ops::{Add,Mul};
+
+use lemke::{LCP,Homotopy};
+
+use super::bimatrix::{Bimatrix,Equilibrium};
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+#[cfg(test)] use super::lemke_howson::von_stengel_3x2;
+
+/*
+ * The equilibrium selected by the linear tracing procedure of Harsanyi
+ * and Selten from the prior  (p, q),  computed by Lemke's algorithm as
+ * in von Stengel, van den Elzen and Talman (2002).  See  tracing_lcp.
+ */
+pub fn tracing_procedure(game: &Bimatrix, p: &[BigRational], q: &[BigRational]) -> Equilibrium {
+    let mut lcp = tracing_lcp(game, p, q);
+    let z = lcp.solve(0).expect("The tracing procedure ends in an equilibrium");
+    game.equilibrium(z[..game.m].to_vec(), z[game.m..game.m + game.n].to_vec())
+}
+
+/*
+ * the path of the tracing procedure as a function of the weight  z0
+ * of the prior, from  z0 = 1  down to the equilibrium at  z0 = 0.
+ * The points are  (x, y, u, v)  as in  tracing_lcp.
+ */
+pub fn tracing_path(game: &Bimatrix, p: &[BigRational], q: &[BigRational]) -> Homotopy {
+    let mut lcp = tracing_lcp(game, p, q);
+    lcp.trace_homotopy();
+    lcp.solve(0).expect("The tracing procedure ends in an equilibrium");
+    lcp.homotopy().unwrap().clone()
+}
+
+/*
+ * With the costs  C  and  D  of  A  and  B'  (see  Bimatrix::costs)  the LCP
+ *
+ *     w_x = C y  + z0 Cq  - u 1  >= 0,     x >= 0
+ *     w_y = D x  + z0 Dp  - v 1  >= 0,     y >= 0
+ *     w_u = 1'x  + z0 - 1        >= 0,     u >= 0
+ *     w_v = 1'y  + z0 - 1        >= 0,     v >= 0
+ *
+ * where the prior is the covering vector (as  addCoveringVector  builds
+ * it from the random priors of the sequence form).  On the Lemke path
+ * x  and  y  have weight  1 - z0,  and each player best responds to the
+ * other's strategy mixed with the prior at weight  z0,  with least cost
+ * u  resp.  v.  z0  enters at 1 where both are best responses to the prior.
+ */
+pub fn tracing_lcp(game: &Bimatrix, p: &[BigRational], q: &[BigRational]) -> LCP {
+
+    let (m, n) = (game.m, game.n);
+    if !is_mixed_strategy(p, m) || !is_mixed_strategy(q, n) {
+        panic!("The prior must be a pair of mixed strategies with {} and {} entries", m, n);
+    }
+
+    let costs = game.costs();
+    let size = m + n + 2;
+    let (u, v) = (m + n, m + n + 1);
+
+    let mut lcp_m = vec![BigRational::zero(); size * size];
+    let mut d = vec![BigRational::one(); size];
+    for i in 0..m {
+        for j in 0..n {
+            lcp_m[i*size + m + j] = costs.a(i, j).clone();
+            lcp_m[(m + j)*size + i] = costs.b(i, j).clone();
+        }
+        lcp_m[i*size + u] = -BigRational::one();
+        lcp_m[u*size + i] = BigRational::one();
+        d[i] = (0..n).fold(BigRational::zero(), |acc, j| acc.add(costs.a(i, j).mul(&q[j])));
+    }
+    for j in 0..n {
+        lcp_m[(m + j)*size + v] = -BigRational::one();
+        lcp_m[v*size + m + j] = BigRational::one();
+        d[m + j] = (0..m).fold(BigRational::zero(), |acc, i| acc.add(costs.b(i, j).mul(&p[i])));
+    }
+
+    let mut lcp_q = vec![BigRational::zero(); m + n];
+    lcp_q.extend(vec![-BigRational::one(); 2]);
+
+    let mut names: Vec<String> = (0..m).map(|i| format!("x{}", i)).chain((0..n).map(|j| format!("y{}", j))).collect();
+    names.extend(vec!["u".to_string(), "v".to_string()]);
+
+    let mut lcp = LCP::with_names(lcp_m, lcp_q, names);
+    lcp.add_covering_vector(d);
+    lcp
+}
+
+fn is_mixed_strategy(strategy: &[BigRational], len: usize) -> bool {
+    let sum = strategy.iter().fold(BigRational::zero(), |acc, s| acc.add(s));
+    strategy.len() == len && strategy.iter().all(|s| !s.is_negative()) && sum.is_one()
+}
+
+#[test]
+fn tracing_selects_best_response_to_prior() {
+
+    let game = von_stengel_3x2();
+
+    // the best responses to this prior are an equilibrium
+    let equilibrium = tracing_procedure(&game, &into_bigrats(vec![1, 0, 0]), &into_bigrats(vec![1, 0]));
+    assert_eq!(into_bigrats(vec![1, 0, 0]), equilibrium.x);
+    assert_eq!(into_bigrats(vec![1, 0]), equilibrium.y);
+
+    for (p, q) in [
+        (vec![rat(1, 3), rat(1, 3), rat(1, 3)], vec![rat(1, 2), rat(1, 2)]),
+        (into_bigrats(vec![0, 0, 1]), into_bigrats(vec![0, 1])),
+        (vec![rat(0, 1), rat(1, 4), rat(3, 4)], vec![rat(1, 5), rat(4, 5)]),
+    ] {
+        let equilibrium = tracing_procedure(&game, &p, &q);
+        assert!(game.is_equilibrium(&equilibrium.x, &equilibrium.y));
+    }
+}
+
+#[test]
+fn tracing_path_runs_from_prior_to_equilibrium() {
+
+    let game = von_stengel_3x2();
+    let p = vec![rat(1, 3), rat(1, 3), rat(1, 3)];
+    let q = vec![rat(1, 2), rat(1, 2)];
+    let equilibrium = tracing_procedure(&game, &p, &q);
+    let path = tracing_path(&game, &p, &q);
+
+    // z0 = 1  where the path leaves the prior, nothing played yet
+    assert_eq!(BigRational::one(), path.segments[0].z0_start);
+    assert!(path.segments[0].z_start[..5].iter().all(|z| z.is_zero()));
+
+    let end = path.segments.last().unwrap().point(&BigRational::zero()).unwrap();
+    assert_eq!(equilibrium.x, end[..3].to_vec());
+    assert_eq!(equilibrium.y, end[3..5].to_vec());
+}
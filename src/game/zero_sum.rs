@@ -0,0 +1,104 @@
+use num::rational::BigRational;
+use num::traits::One;
+
+use std::ops::{Div,Neg,Sub};
+
+use lemke::lp::{maximize,LPResult};
+
+use super::bimatrix::{Bimatrix,shift_to_positive};
+
+#[cfg(test)] use lemke::{into_bigrats,rat};
+#[cfg(test)] use super::support_enumeration::support_enumeration;
+
+/*
+ * Two-player zero-sum game: the row player receives  A[i][j]  from
+ * the column player (m x n, row-major)
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct ZeroSumGame {
+    pub m: usize,
+    pub n: usize,
+    pub a: Vec<BigRational>,
+}
+
+/*
+ * optimal strategies  x  (maximizing) and  y  (minimizing) with
+ * value = x'Ay
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct ZeroSumSolution {
+    pub value: BigRational,
+    pub x: Vec<BigRational>,
+    pub y: Vec<BigRational>,
+}
+
+impl ZeroSumGame {
+
+    pub fn new(m: usize, n: usize, a: Vec<BigRational>) -> ZeroSumGame {
+        if m == 0 || n == 0 {
+            panic!("Each player needs at least one strategy but the game is {}x{}", m, n);
+        }
+        if a.len() != m * n {
+            panic!("A must be {}x{} but has {} entries", m, n, a.len());
+        }
+        ZeroSumGame { m, n, a }
+    }
+
+    // the bimatrix game with  B = -A
+    pub fn to_bimatrix(&self) -> Bimatrix {
+        Bimatrix::new(self.m, self.n, self.a.clone(), self.a.iter().map(|a_ij| a_ij.neg()).collect())
+    }
+
+    /*
+     * With  A  shifted to positive payoffs  A + c,  the LP of the
+     * column player
+     *
+     *     max 1'y'  s.t.  (A + c) y' <= 1,  y' >= 0
+     *
+     * has optimum  V = 1 / (value + c),  with  y = y'/V  and the dual
+     * solution  x'  giving  x = x'/V.  Solved by  lemke::lp::maximize,
+     * so everything is exact.
+     */
+    pub fn solve(&self) -> ZeroSumSolution {
+
+        let positive = shift_to_positive(&self.a);
+        let shift = positive[0].clone().sub(&self.a[0]);
+
+        let ones = |len: usize| vec![BigRational::one(); len];
+        match maximize(positive, ones(self.m), ones(self.n)) {
+            LPResult::Optimal { x: y, y: x, value } => ZeroSumSolution {
+                value: BigRational::one().div(&value).sub(&shift),
+                x: x.iter().map(|x_i| x_i.div(&value)).collect(),
+                y: y.iter().map(|y_j| y_j.div(&value)).collect(),
+            },
+            _ => panic!("The LP of a zero-sum game with positive payoffs has an optimum"),
+        }
+    }
+}
+
+#[test]
+fn zero_sum_solves_matching_pennies() {
+
+    let game = ZeroSumGame::new(2, 2, into_bigrats(vec![1, -1, -1, 1]));
+    let solution = game.solve();
+
+    assert_eq!(rat(0, 1), solution.value);
+    assert_eq!(vec![rat(1, 2), rat(1, 2)], solution.x);
+    assert_eq!(vec![rat(1, 2), rat(1, 2)], solution.y);
+}
+
+#[test]
+fn zero_sum_solves_rectangular_game() {
+
+    // the third column is never played
+    let game = ZeroSumGame::new(2, 3, vec![rat(3, 1), rat(-1, 1), rat(4, 1), rat(-2, 1), rat(1, 2), rat(5, 1)]);
+    let solution = game.solve();
+
+    assert_eq!(vec![rat(5, 13), rat(8, 13)], solution.x);
+    assert_eq!(vec![rat(3, 13), rat(10, 13), rat(0, 1)], solution.y);
+    assert_eq!(rat(-1, 13), solution.value);
+
+    let bimatrix = game.to_bimatrix();
+    assert!(bimatrix.is_equilibrium(&solution.x, &solution.y));
+    assert_eq!(vec![bimatrix.equilibrium(solution.x.clone(), solution.y.clone())], support_enumeration(&bimatrix));
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool,Ordering};
+use std::time::{Duration,Instant};
+
+/*
+ * Shared flag to stop a running Lemke solve from another thread.
+ * Clones share the flag, so one clone can be handed to the solver
+ * while another is kept to cancel it.
+ */
+#[derive(Clone,Debug,Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+
+    pub fn new() -> CancellationToken {
+        CancellationToken { cancelled: Arc::new(AtomicBool::new(false)) }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Interruption {
+    Cancelled,
+    PivotLimit,
+    TimeLimit,
+}
+
+/*
+ * Limits for one call of  LCP::solve_within, checked between pivots.
+ * pivot_max == 0  means no pivot limit (as in  lemke_with_pivot_max)
+ */
+#[derive(Clone,Debug,Default)]
+pub struct Budget {
+    pub pivot_max: usize,
+    pub time_max: Option<Duration>,
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl Budget {
+
+    pub fn unlimited() -> Budget {
+        Budget::default()
+    }
+
+    pub fn pivots(pivot_max: usize) -> Budget {
+        Budget { pivot_max, ..Budget::default() }
+    }
+
+    pub fn with_time_max(mut self, time_max: Duration) -> Budget {
+        self.time_max = Some(time_max);
+        self
+    }
+
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Budget {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /*
+     * why the solve has to stop before its next pivot, if it has to,
+     * given  pivots  performed since  start
+     */
+    pub fn exceeded(&self, pivots: usize, start: &Instant) -> Option<Interruption> {
+        if let Some(ref cancellation) = self.cancellation {
+            if cancellation.is_cancelled() {
+                return Some(Interruption::Cancelled);
+            }
+        }
+        if self.pivot_max != 0 && pivots >= self.pivot_max {
+            return Some(Interruption::PivotLimit);
+        }
+        if let Some(time_max) = self.time_max {
+            if start.elapsed() >= time_max {
+                return Some(Interruption::TimeLimit);
+            }
+        }
+        None
+    }
+}
+
+#[test]
+fn budget_checks_limits() {
+
+    let start = Instant::now();
+    assert_eq!(None, Budget::unlimited().exceeded(1000, &start));
+    assert_eq!(None, Budget::pivots(3).exceeded(2, &start));
+    assert_eq!(Some(Interruption::PivotLimit), Budget::pivots(3).exceeded(3, &start));
+    assert_eq!(Some(Interruption::TimeLimit), Budget::unlimited().with_time_max(Duration::from_secs(0)).exceeded(0, &start));
+
+    let token = CancellationToken::new();
+    let budget = Budget::unlimited().with_cancellation(token.clone());
+    assert_eq!(None, budget.exceeded(0, &start));
+    token.cancel();
+    assert_eq!(Some(Interruption::Cancelled), budget.exceeded(0, &start));
+}
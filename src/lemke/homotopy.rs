@@ -0,0 +1,137 @@
+use num::rational::BigRational;
+use num::traits::{Zero,One,Signed};
+
+use std::ops::{Add,Div,Mul,Sub};
+
+use super::tableau_vars::TableauVariable;
+
+/*
+ * One edge of the Lemke path.  entering  increases from zero at the
+ * vertex where  z0 = z0_start  and  z = z_start,  until  z0 = z0_end
+ * where the next pivot happens.  Along the edge
+ *
+ *     z(z0) = z_start + (z0 - z0_start) * slope
+ *
+ * slope  is None if  z0  stays constant on the edge (rate zero),
+ * which can only happen in degenerate cases.  z0_end  is None for a
+ * ray: the primary ray  z = 0  for  z0 >= z0_start  before the first
+ * vertex, or the secondary ray of ray termination, along which  z0
+ * moves in the direction of  rate  = dz0 / d(entering).
+ */
+#[derive(Debug,Clone)]
+pub struct HomotopySegment {
+	pub entering: TableauVariable,
+	pub z0_start: BigRational,
+	pub z0_end: Option<BigRational>,
+	pub z_start: Vec<BigRational>,
+	pub slope: Option<Vec<BigRational>>,
+	pub rate: BigRational,
+}
+
+impl HomotopySegment {
+
+	// whether the segment has a point with this  z0
+	pub fn contains(&self, z0: &BigRational) -> bool {
+		match self.z0_end {
+			Some(ref z0_end) => {
+				(z0 >= &self.z0_start && z0 <= z0_end) || (z0 <= &self.z0_start && z0 >= z0_end)
+			},
+			None => {
+				if self.rate.is_positive() {
+					z0 >= &self.z0_start
+				} else if self.rate.is_negative() {
+					z0 <= &self.z0_start
+				} else {
+					z0 == &self.z0_start
+				}
+			},
+		}
+	}
+
+	/*
+	 * z  at  z0  on this segment, None if the segment has no such point.
+	 * If  z0  is constant on the segment, its first vertex is returned.
+	 */
+	pub fn point(&self, z0: &BigRational) -> Option<Vec<BigRational>> {
+		if !self.contains(z0) {
+			return None;
+		}
+		match self.slope {
+			Some(ref slope) => {
+				let t = z0.sub(&self.z0_start);
+				Some(self.z_start.iter().zip(slope.iter()).map(|(z_i, slope_i)| z_i.add(slope_i.mul(&t))).collect())
+			},
+			None => Some(self.z_start.clone()),
+		}
+	}
+}
+
+/*
+ * The Lemke path as a piecewise linear function of  z0,  from the
+ * primary ray to the solution at  z0 = 0  (or to a secondary ray).
+ * z0  need not decrease monotonically, so a value of  z0  can be hit
+ * by several segments.
+ */
+#[derive(Debug,Clone)]
+pub struct Homotopy {
+	pub segments: Vec<HomotopySegment>,
+	z0: TableauVariable,
+}
+
+impl Homotopy {
+
+	// z0  is the covering variable of the LCP, entering along the primary ray
+	pub fn new(z0: TableauVariable) -> Homotopy {
+		Homotopy { segments: Vec::new(), z0 }
+	}
+
+	// all points of the path with this  z0,  in path order
+	pub fn points(&self, z0: &BigRational) -> Vec<Vec<BigRational>> {
+		self.segments.iter().filter_map(|segment| segment.point(z0)).collect()
+	}
+
+	// the first point of the path with this  z0
+	pub fn point(&self, z0: &BigRational) -> Option<Vec<BigRational>> {
+		self.segments.iter().filter_map(|segment| segment.point(z0)).next()
+	}
+
+	/*
+	 * record the vertex reached by a pivot:  z0  and  z  there, and
+	 * if the path goes on,  entering  with the direction  (dz, dz0)
+	 * in which  z  and  z0  move per unit of  entering
+	 */
+	pub fn add_vertex(&mut self, z0: BigRational, z: Vec<BigRational>, next: Option<(TableauVariable, Vec<BigRational>, BigRational)>) {
+
+		match self.segments.last_mut() {
+			Some(segment) => segment.z0_end = Some(z0.clone()),
+			None => {
+				// primary ray along which  z0  came down to this vertex
+				let n = z.len();
+				self.segments.push(HomotopySegment {
+					entering: self.z0.clone(),
+					z0_start: z0.clone(),
+					z0_end: None,
+					z_start: z.clone(),
+					slope: Some(vec![BigRational::zero(); n]),
+					rate: BigRational::one(),
+				});
+			}
+		}
+
+		if let Some((entering, dz, dz0)) = next {
+			let slope = if dz0.is_zero() {
+				None
+			} else {
+				Some(dz.iter().map(|dz_i| dz_i.div(&dz0)).collect())
+			};
+			self.segments.push(HomotopySegment {
+				entering,
+				z0_start: z0,
+				z0_end: None,
+				z_start: z,
+				slope,
+				rate: dz0,
+			});
+		}
+	}
+}
@@ -0,0 +1,178 @@
+use num::bigint::BigInt;
+use num::integer::Integer;
+use num::traits::{Zero,One,Signed};
+
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::ops::{Add,Div,Mul,Neg,Sub};
+
+#[cfg(feature = "gmp")] use num::bigint::Sign;
+#[cfg(feature = "gmp")] use rug;
+#[cfg(feature = "gmp")] use rug::integer::Order;
+#[cfg(feature = "gmp")] use std::cmp::Ordering;
+
+/*
+ * The integer arithmetic of the fraction-free tableau.  Implemented for
+ * num's  BigInt  and, with the  gmp  feature, for GMP integers (rug).
+ * Values cross the  TableauEngine  boundary as  BigInt,  so only
+ * from_bigint  and  to_bigint  convert; the pivots stay in  Self.
+ * Methods take references and are named apart from the  num  and
+ * std::ops  traits so both can be in scope for  BigInt.
+ */
+pub trait TableauInt: Clone + Ord + Debug + Send + Sync {
+    fn from_bigint(value: BigInt) -> Self;
+    fn to_bigint(&self) -> Cow<'_, BigInt>;
+
+    fn is_zero(&self) -> bool;
+    fn is_one(&self) -> bool;
+    fn is_negative(&self) -> bool;
+    fn abs_value(&self) -> Self;
+    fn negated(&self) -> Self;
+    fn add_ref(&self, other: &Self) -> Self;
+    fn sub_ref(&self, other: &Self) -> Self;
+    fn mul_ref(&self, other: &Self) -> Self;
+    // other  must divide  self
+    fn div_exact(&self, other: &Self) -> Self;
+    fn gcd_ref(&self, other: &Self) -> Self;
+    fn bits(&self) -> u64;
+}
+
+impl TableauInt for BigInt {
+
+    fn from_bigint(value: BigInt) -> BigInt {
+        value
+    }
+
+    fn to_bigint(&self) -> Cow<'_, BigInt> {
+        Cow::Borrowed(self)
+    }
+
+    fn is_zero(&self) -> bool {
+        Zero::is_zero(self)
+    }
+
+    fn is_one(&self) -> bool {
+        One::is_one(self)
+    }
+
+    fn is_negative(&self) -> bool {
+        Signed::is_negative(self)
+    }
+
+    fn abs_value(&self) -> BigInt {
+        Signed::abs(self)
+    }
+
+    fn negated(&self) -> BigInt {
+        self.neg()
+    }
+
+    fn add_ref(&self, other: &BigInt) -> BigInt {
+        self.add(other)
+    }
+
+    fn sub_ref(&self, other: &BigInt) -> BigInt {
+        self.sub(other)
+    }
+
+    fn mul_ref(&self, other: &BigInt) -> BigInt {
+        self.mul(other)
+    }
+
+    fn div_exact(&self, other: &BigInt) -> BigInt {
+        self.div(other)
+    }
+
+    fn gcd_ref(&self, other: &BigInt) -> BigInt {
+        Integer::gcd(self, other)
+    }
+
+    fn bits(&self) -> u64 {
+        BigInt::bits(self)
+    }
+}
+
+#[cfg(feature = "gmp")]
+impl TableauInt for rug::Integer {
+
+    fn from_bigint(value: BigInt) -> rug::Integer {
+        let (sign, digits) = value.to_bytes_le();
+        let magnitude = rug::Integer::from_digits(&digits, Order::Lsf);
+        if sign == Sign::Minus {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    fn to_bigint(&self) -> Cow<'_, BigInt> {
+        let sign = if self.cmp0() == Ordering::Less {
+            Sign::Minus
+        } else {
+            Sign::Plus
+        };
+        Cow::Owned(BigInt::from_bytes_le(sign, &self.to_digits::<u8>(Order::Lsf)))
+    }
+
+    fn is_zero(&self) -> bool {
+        self.cmp0() == Ordering::Equal
+    }
+
+    fn is_one(&self) -> bool {
+        *self == 1
+    }
+
+    fn is_negative(&self) -> bool {
+        self.cmp0() == Ordering::Less
+    }
+
+    fn abs_value(&self) -> rug::Integer {
+        rug::Integer::from(self.abs_ref())
+    }
+
+    fn negated(&self) -> rug::Integer {
+        rug::Integer::from(-self)
+    }
+
+    fn add_ref(&self, other: &rug::Integer) -> rug::Integer {
+        rug::Integer::from(self + other)
+    }
+
+    fn sub_ref(&self, other: &rug::Integer) -> rug::Integer {
+        rug::Integer::from(self - other)
+    }
+
+    fn mul_ref(&self, other: &rug::Integer) -> rug::Integer {
+        rug::Integer::from(self * other)
+    }
+
+    fn div_exact(&self, other: &rug::Integer) -> rug::Integer {
+        rug::Integer::from(self.div_exact_ref(other))
+    }
+
+    fn gcd_ref(&self, other: &rug::Integer) -> rug::Integer {
+        rug::Integer::from(rug::Integer::gcd_ref(self, other))
+    }
+
+    fn bits(&self) -> u64 {
+        u64::from(self.significant_bits())
+    }
+}
+
+#[cfg(feature = "gmp")]
+#[test]
+fn gmp_integers_round_trip() {
+
+    let values = vec![
+        BigInt::zero(),
+        BigInt::from(-1),
+        BigInt::from(255),
+        BigInt::from(-65536),
+        BigInt::parse_bytes(b"-123456789012345678901234567890", 10).unwrap(),
+    ];
+    for value in values {
+        let gmp = rug::Integer::from_bigint(value.clone());
+        assert_eq!(value, gmp.to_bigint().into_owned());
+        assert_eq!(value.bits(), TableauInt::bits(&gmp));
+    }
+}
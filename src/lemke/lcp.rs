@@ -1,15 +1,28 @@
 use num::bigint::BigInt;
 use num::rational::{Ratio,BigRational};
-use num::traits::{One,Zero};
+use num::traits::{One,Zero,Signed};
 
-use std::ops::{Div,Rem,Mul};
+use std::fmt;
+use std::ops::{Div,Rem,Mul,Neg};
 use std::cmp::{Ordering};
+use std::mem;
+use std::time::Instant;
 
-use super::tableau::Tableau;
-use super::tableau_vars::{TableauVariables};
-use super::lex_min_ratio::lexminratio;
+use super::tableau::{TableauEngine,default_tableau};
+use super::tableau_vars::{TableauVariable,TableauVariables};
+use super::lex_min_ratio::LexStats;
+use super::leaving_rule::{LeavingRule,LexMinRatio};
+use super::budget::{Budget,Interruption};
+use super::lcp_state::{LCPState,LCPPhase,LCPError};
+use super::homotopy::Homotopy;
+#[cfg(feature = "multimodular")] use super::modular_tableau::ModularTableau;
 
 #[cfg(test)] use num::traits::{FromPrimitive,ToPrimitive};
+#[cfg(test)] use std::time::Duration;
+#[cfg(test)] use std::ops::Add;
+#[cfg(test)] use std::cell::RefCell;
+#[cfg(test)] use std::rc::Rc;
+#[cfg(test)] use super::budget::CancellationToken;
 
 // LCP (aka. Linear Complementarity Problem)
 // =============================================================================
@@ -31,7 +44,7 @@ pub struct LCP {
 	pub n: usize,
     d: Vec<BigRational>,
 
-	pub tableau: Tableau,
+	pub tableau: Box<dyn TableauEngine>,
     pub vars: TableauVariables,
 
 	/* scale factors for variables z
@@ -39,12 +52,30 @@ pub struct LCP {
 	 * scfa[Z(1..n)] for cols of  M
 	 * result variables to be multiplied with these
 	 */
-	scale_factors: Vec<BigInt>,
+	scale_factors: Vec<BigRational>,
+	normalized: bool,
+
+	// largest bit length of a tableau entry after each pivot
+	pub max_bits: Vec<u64>,
+
+	pub stats: LexStats,
+	leaving_rule: Box<dyn LeavingRule>,
+
+	pub pivot_count: usize,
+	phase: Phase,
+
+	// the path as a function of  z0,  if traced
+	homotopy: Option<Homotopy>,
+
+	// called before each pivot, see  set_pivot_trace
+	pivot_trace: Option<PivotTrace>,
 }
 
+type PivotTrace = Box<dyn FnMut(usize, &str, &str)>;
+
 impl LCP {
 
-    fn new(m: Vec<BigRational>, q: Vec<BigRational>) -> LCP {
+    pub fn new(m: Vec<BigRational>, q: Vec<BigRational>) -> LCP {
 
     	if m.len()%q.len() != 0 {
     		panic!("M and q are not right dimensions");  // TODO: return Result instead
@@ -52,7 +83,6 @@ impl LCP {
 
     	let ncols = m.len() / q.len();
     	let nrows = q.len();
-    	println!("Creating LCP with matrix M [{}x{}] and vector q [{}x1]\n", nrows, ncols, nrows);
 
     	if ncols != nrows {
     		panic!("M must be a square matrix but was {}x{}", nrows, ncols);
@@ -65,17 +95,43 @@ impl LCP {
             d: vec![BigRational::zero(); nrows],
             n: nrows,
             vars: TableauVariables::new(nrows),
-            tableau: Tableau::new(nrows),
-        	scale_factors: vec![BigInt::zero(); nrows+2],
+            tableau: default_tableau(nrows),
+        	scale_factors: vec![BigRational::zero(); nrows+2],
+            normalized: false,
+            max_bits: Vec::new(),
+            stats: LexStats::new(),
+            leaving_rule: Box::new(LexMinRatio),
+            pivot_count: 0,
+            phase: Phase::Start,
+            homotopy: None,
+            pivot_trace: None,
         };
     	lcp.init_tableau();
 
     	lcp
     }
 
+    /*
+     * LCP whose  z[i]  is called  names[i]  (and  w[i]  its slack)
+     * in pivot traces (see  set_pivot_trace), errors, the tableau display
+     * and  format_solution
+     */
+    pub fn with_names(m: Vec<BigRational>, q: Vec<BigRational>, names: Vec<String>) -> LCP {
+        let mut lcp = LCP::new(m, q);
+        lcp.vars.set_names(names);
+        lcp
+    }
+
+    // one  name = value  line per entry of a solution  z
+    pub fn format_solution(&self, z: &[BigRational]) -> String {
+        z.iter().enumerate()
+            .map(|(i, z_i)| format!("{} = {}\n", self.vars.name(&self.vars.z(i + 1)), z_i))
+            .collect()
+    }
+
     fn init_tableau(&mut self) {
 
-    	for j in 1..self.tableau.ncols {
+    	for j in 1..self.tableau.ncols() {
 
             let scale_factor = {
         		self.compute_scale_factor(self.n, |i: usize| {
@@ -87,7 +143,7 @@ impl LCP {
         		})
             };
 
-    		for i in 0..self.tableau.nrows {
+    		for i in 0..self.tableau.nrows() {
                 let value = {
         			let rat = if j == self.n+1 {
         				&self.q[i]
@@ -99,7 +155,7 @@ impl LCP {
         			/* where the system is here         -Iw + dz_0 + Mz = -q    */
         			/* cols of  q  will be negated after first min ratio test   */
         			/* A[i][j] = num * (scfa[j] / den),  fraction is integral       */
-                    rat.numer().mul(&scale_factor).div(rat.denom())
+                    rat.mul(&scale_factor).to_integer()
                 };
     			self.tableau.set(i, j, value);
     		}
@@ -110,8 +166,10 @@ impl LCP {
     /*
      * compute lcm  of denominators for  col  j  of  A
      * Necessary for converting fractions to integers and back again
+     * if normalized, pre-scale by the gcd of the resulting integers,
+     * so the scale factor is  lcm / gcd
      */
-    fn compute_scale_factor<'a, F>(&'a self, n: usize, vec: F) -> BigInt where F : Fn(usize) -> &'a BigRational {
+    fn compute_scale_factor<'a, F>(&'a self, n: usize, vec: F) -> BigRational where F : Fn(usize) -> &'a BigRational {
 
     	let mut lcm = BigInt::one();
     	for i in 0..n {
@@ -119,13 +177,24 @@ impl LCP {
             let gcd = LCP::euclid_gcd(&lcm, rat.denom());
     		lcm = lcm.div(&gcd).mul(rat.denom());
     	}
-    	lcm
+
+        let mut gcd = BigInt::zero();
+        if self.normalized {
+        	for i in 0..n {
+                let rat = vec(i);
+                gcd = LCP::euclid_gcd(&gcd, &rat.numer().mul(&lcm).div(rat.denom()));
+            }
+        }
+        if gcd.is_zero() {
+            gcd = BigInt::one();
+        }
+    	Ratio::new(lcm, gcd.abs())
     }
 
     fn euclid_gcd(a: &BigInt, b: &BigInt) -> BigInt {
         let mut a = a.clone();
         let mut b = b.clone();
-        while BigInt::zero().eq(&b) {
+        while !b.is_zero() {
             let t = b;
             b = a.rem(&t);
             a = t;
@@ -140,97 +209,487 @@ impl LCP {
 
     	let scale_factor = self.compute_scale_factor(self.n, |i| &self.d[i]);
 
-    	for i in 0..self.tableau.nrows {
+    	for i in 0..self.tableau.nrows() {
     		let rat = &self.d[i];
-    		let value = rat.numer().mul(&scale_factor).div(rat.denom());
+    		let value = rat.mul(&scale_factor).to_integer();
     		self.tableau.set(i, 0, value)
     	}
 
         self.scale_factors[0] = scale_factor;
     }
+
+    /*
+     * keep entries of the integer tableau small: pre-scale each column
+     * by the gcd of its entries and divide each row by its gcd after
+     * every pivot (see  Tableau::normalize_rows).  Must be called
+     * before  solve.
+     */
+    pub fn normalize(&mut self) {
+
+        if !matches!(self.phase, Phase::Start) {
+            panic!("Cannot normalize an LCP after Lemke has started");
+        }
+
+        self.normalized = true;
+        self.refill_tableau();
+    }
+
+    /*
+     * pivot modulo word-size primes instead of on big integers
+     * (see  ModularTableau).  Must be called before  solve.
+     */
+    #[cfg(feature = "multimodular")]
+    pub fn use_multimodular(&mut self) {
+
+        if !matches!(self.phase, Phase::Start) {
+            panic!("Cannot change the tableau engine after Lemke has started");
+        }
+
+        self.tableau = Box::new(ModularTableau::new(self.n));
+        self.refill_tableau();
+    }
+
+    // set up the tableau again from  M, q  and  d
+    fn refill_tableau(&mut self) {
+        self.init_tableau();
+        let d = mem::take(&mut self.d);
+        self.add_covering_vector(d);
+        if self.normalized {
+            self.tableau.normalize_rows();
+        }
+    }
+
+    /*
+     * record the Lemke path in  solve  as a piecewise linear function
+     * of  z0,  see  homotopy.  Must be called before  solve.
+     */
+    pub fn trace_homotopy(&mut self) {
+
+        if !matches!(self.phase, Phase::Start) {
+            panic!("Cannot trace the homotopy after Lemke has started");
+        }
+
+        self.homotopy = Some(Homotopy::new(self.vars.z(0)));
+    }
+
+    // the path so far if  trace_homotopy  was called
+    pub fn homotopy(&self) -> Option<&Homotopy> {
+        self.homotopy.as_ref()
+    }
+
+    // rule for the leaving variable in  solve, lexicographic by default
+    pub fn set_leaving_rule<R: LeavingRule + 'static>(&mut self, rule: R) {
+        self.leaving_rule = Box::new(rule);
+    }
+
+    /*
+     * call  trace  before each pivot of  solve  with the pivot count and
+     * the names of the entering and leaving variables.  Nothing is
+     * traced by default.
+     */
+    pub fn set_pivot_trace<F: FnMut(usize, &str, &str) + 'static>(&mut self, trace: F) {
+        self.pivot_trace = Some(Box::new(trace));
+    }
 }
 
 /*
- * asserts that  d >= 0  and not  q >= 0  (o/w trivial sol)
- * and that q[i] < 0  implies  d[i] > 0
+ * asserts that  d >= 0  and that q[i] < 0  implies  d[i] > 0
+ * returns whether  q >= 0  (trivial solution  z=0)
  */
- // TODO: don't error on trivial solution... just return it
-fn validate_inputs(q: &Vec<BigRational>, d: &Vec<BigRational>) {
+fn validate_inputs(q: &Vec<BigRational>, d: &Vec<BigRational>, vars: &TableauVariables) -> bool {
 
 	let mut is_q_pos = true;
 	for i in 0..q.len() {
 		if d[i].cmp(&Ratio::zero()) == Ordering::Less {
-			panic!("Covering vector  d[{}] = {} negative. Cannot start Lemke.", i+1, d[i]);
+			panic!("Covering vector  d[{}] = {} for {} negative. Cannot start Lemke.", i+1, d[i], vars.name(&vars.z(i+1)));
 		} else if q[i].cmp(&Ratio::zero()) == Ordering::Less {
 			is_q_pos = false;
 			if d[i].is_zero() {
-				panic!("Covering vector  d[{}] = 0  where  q[{}] = {}  is negative. Cannot start Lemke.", i+1, i+1, q[i]);
+				panic!("Covering vector  d[{}] = 0  where  q[{}] = {}  is negative for {}. Cannot start Lemke.", i+1, i+1, q[i], vars.name(&vars.z(i+1)));
 			}
 		}
 	}
 
-	if is_q_pos {
-		panic!("No need to start Lemke since  q>=0. Trivial solution  z=0.");
-	}
+	is_q_pos
 }
 
-fn lemke(m: Vec<BigRational>, q: Vec<BigRational>, d: Vec<BigRational>) -> Vec<BigRational> {
-	lemke_with_pivot_max(m, q, d, 0)
+/*
+ * Ray termination: the entering variable has no positive entry
+ * in its column and can be increased without bound.
+ * z  and  z0  give the direction of the unbounded edge
+ * (Z(1)..Z(n) become indices 0..n-1, as in the solution)
+ */
+#[derive(Debug,Clone)]
+pub struct RayTermination {
+	pub enter: TableauVariable,
+	pub z: Vec<BigRational>,
+	pub z0: BigRational,
 }
 
-// LemkeWithPivotMax solves the linear complementarity probelm via Lemke's algorithm.
-// It will only perform up to maxCount pivots before exiting.
-// TODO: ray termination...
-fn lemke_with_pivot_max(m: Vec<BigRational>, q: Vec<BigRational>, d: Vec<BigRational>, pivot_max: usize) -> Vec<BigRational> {
+pub fn lemke(m: Vec<BigRational>, q: Vec<BigRational>, d: Vec<BigRational>) -> Result<Vec<BigRational>, RayTermination> {
+	match lemke_with_pivot_max(m, q, d, 0) {
+		Ok(z) => Ok(z),
+		Err(Unsolved::Ray(ray)) => Err(ray),
+		Err(Unsolved::Interrupted(_)) => unreachable!("Lemke without a pivot limit is not interrupted"),
+	}
+}
 
-	validate_inputs(&q, &d);
+// LemkeWithPivotMax solves the linear complementarity probelm via Lemke's algorithm.
+// It will only perform up to maxCount pivots before exiting with  Unsolved::Interrupted.
+pub fn lemke_with_pivot_max(m: Vec<BigRational>, q: Vec<BigRational>, d: Vec<BigRational>, pivot_max: usize) -> Result<Vec<BigRational>, Unsolved> {
 
 	// TODO: better way?
     let mut lcp = LCP::new(m, q);
 	lcp.add_covering_vector(d);
+	lcp.solve(pivot_max)
+}
 
-	let mut enter = lcp.vars.z(0); // z0 enters the basis to obtain lex-feasible solution
-	let (mut leave, mut z0_can_leave) = lexminratio(&lcp.tableau, &lcp.vars, &enter);
+/*
+ * why  LCP::solve_within  returned without a solution
+ * Interrupted  leaves the LCP in a state where  solve_within
+ * can be called again to resume with the next pivot
+ */
+#[derive(Debug)]
+pub enum Unsolved {
+	Ray(RayTermination),
+	Interrupted(Interruption),
+}
 
-	lcp.vars.negate_rhs(&mut lcp.tableau); // now give the entering q-col its correct sign
+// where the Lemke run on an LCP stands between calls
+enum Phase {
+	Start,
+	Pivot { enter: TableauVariable, leave: TableauVariable, z0_can_leave: bool },
+	Done,
+	Ray(RayTermination),
+}
 
-	let mut pivot_count = 1;
-	loop {
+impl LCP {
 
-		println!("{}. entering: {}, leaving: {}", pivot_count, enter.to_string(), leave.to_string());
+	/*
+	 * runs Lemke's algorithm on this LCP with its covering vector,
+	 * performing at most  pivot_max  pivots (0 for no limit),
+	 * see  solve_within  for the  Unsolved  outcomes
+	 * leaving variables are chosen by the leaving rule and their
+	 * tie-breaking statistics are recorded in  stats
+	 */
+	pub fn solve(&mut self, pivot_max: usize) -> Result<Vec<BigRational>, Unsolved> {
+		self.solve_within(&Budget::pivots(pivot_max))
+	}
 
-		lcp.vars.pivot(&mut lcp.tableau, &leave, &enter);
+	/*
+	 * as  solve, but stops before the next pivot once  budget  is used up
+	 * (pivots and time are counted from this call), returning
+	 * Unsolved::Interrupted.  Calling again resumes where it stopped.
+	 */
+	pub fn solve_within(&mut self, budget: &Budget) -> Result<Vec<BigRational>, Unsolved> {
+
+		let start = Instant::now();
+
+		let (mut enter, mut leave, mut z0_can_leave) = match mem::replace(&mut self.phase, Phase::Done) {
+			Phase::Start => {
+				if validate_inputs(&self.q, &self.d, &self.vars) {
+					self.vars.negate_rhs(&mut *self.tableau);  // as if started, see  rhs_negated
+					return Ok(vec![BigRational::zero(); self.n]);  // trivial solution  z=0
+				}
+
+				let enter = self.vars.z(0); // z0 enters the basis to obtain lex-feasible solution
+				let (leave, z0_can_leave) = self.leaving_rule.leaving(&*self.tableau, &self.vars, &enter, &mut self.stats).unwrap();  // d > 0 where q < 0
+
+				self.vars.negate_rhs(&mut *self.tableau); // now give the entering q-col its correct sign
+				(enter, leave, z0_can_leave)
+			},
+			Phase::Pivot { enter, leave, z0_can_leave } => (enter, leave, z0_can_leave),
+			Phase::Done => return Ok(self.vars.solution(&*self.tableau, &self.scale_factors)),
+			Phase::Ray(ray) => {
+				self.phase = Phase::Ray(ray.clone());
+				return Err(Unsolved::Ray(ray));
+			},
+		};
+
+		let mut pivots = 0;
+		loop {
+
+			if let Some(interruption) = budget.exceeded(pivots, &start) {
+				self.phase = Phase::Pivot { enter, leave, z0_can_leave };
+				return Err(Unsolved::Interrupted(interruption));
+			}
 
-		if z0_can_leave {
-			break; // z0 will have a value of zero but may still be basic... amend?
+			self.pivot_count += 1;
+			pivots += 1;
+			if let Some(ref mut trace) = self.pivot_trace {
+				trace(self.pivot_count, &self.vars.name(&enter), &self.vars.name(&leave));
+			}
+
+			self.vars.pivot(&mut *self.tableau, &leave, &enter);
+			if let Some(bits) = self.tableau.max_bits() {
+				self.max_bits.push(bits);
+			}
+
+			if z0_can_leave {
+				self.record_vertex(None);
+				break; // z0 will have a value of zero but may still be basic... amend?
+			}
+
+			enter = leave.complement();  // select pivot
+			self.record_vertex(Some(&enter));
+
+			// FIXME: better way?  Maybe rust will fix this...
+			let (next_leave, next_z0_can_leave) = match self.leaving_rule.leaving(&*self.tableau, &self.vars, &enter, &mut self.stats) {
+				Some(next) => next,
+				None => {
+					let (z, z0) = self.vars.ray(&*self.tableau, &self.scale_factors, &enter);
+					let ray = RayTermination { enter, z, z0 };
+					self.phase = Phase::Ray(ray.clone());
+					return Err(Unsolved::Ray(ray));
+				}
+			};
+			leave = next_leave;
+			z0_can_leave = next_z0_can_leave;
 		}
 
-		enter = leave.complement();  // select pivot
+		Ok(self.vars.solution(&*self.tableau, &self.scale_factors))
+	}
+}
 
-		// FIXME: better way?  Maybe rust will fix this...
-		let (next_leave, next_z0_can_leave) = lexminratio(&lcp.tableau, &lcp.vars, &enter);
-        leave = next_leave;
-        z0_can_leave = next_z0_can_leave;
+impl LCP {
 
-		if pivot_count == pivot_max {
-			// maxcount == 0 is equivalent to infinity since pivotcount starts at 1
-			// TODO: negative result...
-			break;
+	// add the current vertex to the homotopy, with the edge along which  enter  increases next
+	fn record_vertex(&mut self, enter: Option<&TableauVariable>) {
+		if let Some(mut homotopy) = self.homotopy.take() {
+			let z0 = self.vars.result(&*self.tableau, &self.scale_factors, &self.vars.z(0));
+			let z = self.vars.solution(&*self.tableau, &self.scale_factors);
+			let next = enter.map(|enter| {
+				let (dz, dz0) = self.vars.ray(&*self.tableau, &self.scale_factors, enter);
+				(enter.clone(), dz, dz0)
+			});
+			homotopy.add_vertex(z0, z, next);
+			self.homotopy = Some(homotopy);
 		}
+	}
 
-		pivot_count += 1;
+	/*
+	 * the current tableau as exact rationals in the variables of the LCP,
+	 * see  TableauVariables::rationals.  Columns are  z0, the cobasic
+	 * variables in tableau order and the RHS, rows follow the basis.
+	 */
+	pub fn tableau_rationals(&self) -> Vec<Vec<BigRational>> {
+		let rhs_col = self.vars.rhs_col();
+		let mut rationals = self.vars.rationals(&*self.tableau, &self.scale_factors);
+		if !self.rhs_negated() {
+			for row in rationals.iter_mut() {
+				row[rhs_col] = (&row[rhs_col]).neg();
+			}
+		}
+		rationals
 	}
 
-	lcp.vars.solution(&lcp.tableau, &lcp.scale_factors)
+	// the RHS column holds  q  rather than  -q  until Lemke starts
+	fn rhs_negated(&self) -> bool {
+		!matches!(self.phase, Phase::Start)
+	}
+}
+
+/*
+ * the integer tableau with its cobasic variables as column headers and
+ * basic variables as row labels, followed by the determinant (a column
+ * of row determinants if rows are normalized) and the scale factors
+ */
+impl fmt::Display for LCP {
+
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+		let nrows = self.tableau.nrows();
+		let ncols = self.tableau.ncols();
+		let rhs_col = self.vars.rhs_col();
+		let determinant = self.tableau.determinant().into_owned();
+		let normalized = (0..nrows).any(|i| *self.tableau.row_determinant(i) != determinant);
+
+		let mut lines = Vec::with_capacity(nrows + 1);
+		let mut header = vec!["basis".to_string()];
+		header.extend((0..ncols).map(|j| self.vars.col_name(j)));
+		if normalized {
+			header.push("det".to_string());
+		}
+		lines.push(header);
+		for i in 0..nrows {
+			let mut line = vec![self.vars.name(&self.vars.from_row(i))];
+			for j in 0..ncols {
+				let entry = self.tableau.entry(i, j).into_owned();
+				line.push(if j == rhs_col && !self.rhs_negated() { entry.neg() } else { entry }.to_string());
+			}
+			if normalized {
+				line.push(self.tableau.row_determinant(i).to_string());
+			}
+			lines.push(line);
+		}
+
+		let widths: Vec<usize> = (0..lines[0].len())
+			.map(|j| lines.iter().map(|line| line[j].len()).max().unwrap())
+			.collect();
+		for line in &lines {
+			let cells: Vec<String> = line.iter().zip(widths.iter())
+				.map(|(cell, &width)| format!("{:>width$}", cell, width = width))
+				.collect();
+			writeln!(f, "{}", cells.join("  "))?;
+		}
+
+		writeln!(f, "det = {}", determinant)?;
+		let scale_factors: Vec<String> = (0..ncols)
+			.map(|j| format!("{} = {}", if j == rhs_col { "RHS".to_string() } else { self.vars.name(&self.vars.z(j)) }, self.scale_factors[j]))
+			.collect();
+		write!(f, "scale factors: {}", scale_factors.join(", "))
+	}
+}
+
+impl LCP {
+
+	/*
+	 * snapshot of the problem, the basis and how far Lemke got,
+	 * see  from_state.  The leaving rule is not part of it.
+	 */
+	pub fn state(&self) -> LCPState {
+
+		let basis = (0..self.n).map(|row| self.vars.from_row(row).to_string()).collect();
+		let (phase, solution) = match self.phase {
+			Phase::Start => (LCPPhase::Start, None),
+			Phase::Pivot { ref enter, .. } => (LCPPhase::Pivoting { entering: enter.to_string() }, None),
+			Phase::Done => (LCPPhase::Done, Some(self.vars.solution(&*self.tableau, &self.scale_factors))),
+			Phase::Ray(ref ray) => (LCPPhase::Ray { entering: ray.enter.to_string() }, None),
+		};
+
+		LCPState {
+			m: self.m.clone(),
+			q: self.q.clone(),
+			d: self.d.clone(),
+			names: self.vars.names().to_vec(),
+			normalized: self.normalized,
+			basis,
+			phase,
+			pivot_count: self.pivot_count,
+			solution,
+		}
+	}
+
+	/*
+	 * LCP as saved by  state.  The tableau is rebuilt by pivoting into
+	 * the saved basis, so an interrupted run resumes with  solve  or
+	 * solve_within  as if it had never stopped.  Fails on a state that
+	 * does not fit together, e.g. a corrupt or outdated record.
+	 */
+	pub fn from_state(state: &LCPState) -> Result<LCP, LCPError> {
+
+		let n = state.q.len();
+		if n == 0 {
+			return Err(state_error("q is empty".to_string()));
+		}
+		if n.checked_mul(n) != Some(state.m.len()) {
+			return Err(state_error(format!("M must be {}x{} but has {} entries", n, n, state.m.len())));
+		}
+		if state.d.len() != n {
+			return Err(state_error(format!("d must have {} entries but has {}", n, state.d.len())));
+		}
+		if !state.names.is_empty() && state.names.len() != n {
+			return Err(state_error(format!("There must be {} names but there are {}", n, state.names.len())));
+		}
+		if state.basis.len() != n {
+			return Err(state_error(format!("The basis must have {} variables but has {}", n, state.basis.len())));
+		}
+
+		let mut lcp = LCP::new(state.m.clone(), state.q.clone());
+		if !state.names.is_empty() {
+			lcp.vars.set_names(state.names.clone());
+		}
+		lcp.add_covering_vector(state.d.clone());
+		if state.normalized {
+			lcp.normalize();
+		}
+		lcp.pivot_count = state.pivot_count;
+
+		let basis = state.basis.iter().map(|var| lcp.parse_var(var)).collect::<Result<Vec<TableauVariable>, LCPError>>()?;
+		if let Some(i) = (0..n).find(|&i| basis[..i].contains(&basis[i])) {
+			return Err(state_error(format!("{} is twice in the basis", state.basis[i])));
+		}
+
+		if state.phase == LCPPhase::Start {
+			return Ok(lcp);
+		}
+
+		lcp.vars.negate_rhs(&mut *lcp.tableau);
+		lcp.pivot_into(&basis)?;
+
+		lcp.phase = match state.phase {
+			LCPPhase::Start => unreachable!(),
+			LCPPhase::Pivoting { ref entering } => {
+				let enter = lcp.parse_cobasic_var(entering)?;
+				let (leave, z0_can_leave) = lcp.leaving_rule.leaving(&*lcp.tableau, &lcp.vars, &enter, &mut lcp.stats)
+					.ok_or_else(|| state_error(format!("{} cannot enter the saved basis", entering)))?;
+				Phase::Pivot { enter, leave, z0_can_leave }
+			},
+			LCPPhase::Done => Phase::Done,
+			LCPPhase::Ray { ref entering } => {
+				let enter = lcp.parse_cobasic_var(entering)?;
+				let (z, z0) = lcp.vars.ray(&*lcp.tableau, &lcp.scale_factors, &enter);
+				Phase::Ray(RayTermination { enter, z, z0 })
+			},
+		};
+		Ok(lcp)
+	}
+
+	fn parse_var(&self, var: &str) -> Result<TableauVariable, LCPError> {
+		self.vars.from_string(var).ok_or_else(|| state_error(format!("Unknown LCP variable {}", var)))
+	}
+
+	fn parse_cobasic_var(&self, var: &str) -> Result<TableauVariable, LCPError> {
+		let parsed = self.parse_var(var)?;
+		if self.vars.is_basic(&parsed) {
+			return Err(state_error(format!("{} is already in the saved basis", var)));
+		}
+		Ok(parsed)
+	}
+
+	/*
+	 * pivot until exactly the variables of  basis  are basic.  A basic
+	 * variable not in  basis  has a nonzero entry in the column of some
+	 * cobasic variable of  basis,  otherwise  basis  is singular.
+	 */
+	fn pivot_into(&mut self, basis: &[TableauVariable]) -> Result<(), LCPError> {
+		while let Some(row) = (0..self.n).find(|&row| !basis.contains(&self.vars.from_row(row))) {
+			let leave = self.vars.from_row(row);
+			let enter = basis.iter()
+				.find(|var| !self.vars.is_basic(var) && !self.tableau.entry(row, self.vars.to_col(var)).is_zero())
+				.ok_or_else(|| state_error("The saved basis is singular".to_string()))?
+				.clone();
+			self.vars.pivot(&mut *self.tableau, &leave, &enter);
+		}
+		Ok(())
+	}
+}
+
+fn state_error(message: String) -> LCPError {
+	LCPError { message }
 }
 
 #[cfg(test)]
-fn into_bigrats(ints: Vec<i64>) -> Vec<BigRational> {
+pub fn into_bigrats(ints: Vec<i64>) -> Vec<BigRational> {
     ints.into_iter()
         .map(|val| { Ratio::from_integer(BigInt::from_i64(val).unwrap()) })
         .collect()
 }
 
+#[cfg(test)]
+pub fn rat(numer: i64, denom: i64) -> BigRational {
+    Ratio::new(BigInt::from_i64(numer).unwrap(), BigInt::from_i64(denom).unwrap())
+}
+
+// the ray a Lemke run ended on
+#[cfg(test)]
+fn ended_on_ray(result: Result<Vec<BigRational>, Unsolved>) -> RayTermination {
+	match result {
+		Err(Unsolved::Ray(ray)) => ray,
+		_ => panic!("should end on a ray"),
+	}
+}
+
 #[test]
 fn lemke2() {
 
@@ -238,7 +697,7 @@ fn lemke2() {
 	let q = into_bigrats(vec![-1, -1]);
 	let d = into_bigrats(vec![2, 1]);
 
-	let z = lemke(m, q, d);
+	let z = lemke(m, q, d).unwrap();
 
 	assert_eq!(2, z.len());
 	assert_eq!(false, z[0].is_integer());
@@ -256,7 +715,7 @@ fn lemke3() {
 	let q = into_bigrats(vec![-3, 6, -1]);
 	let d = into_bigrats(vec![1, 1, 1]);
 
-	let z = lemke(m, q, d);
+	let z = lemke(m, q, d).unwrap();
 
 	assert_eq!(3, z.len());
 	assert_eq!(true, z[0].is_integer());
@@ -266,3 +725,355 @@ fn lemke3() {
 	assert_eq!(true, z[2].is_integer());
 	assert_eq!(3, z[2].numer().to_i64().unwrap());
 }
+
+#[test]
+fn lemke_trivial_solution() {
+
+	let m = into_bigrats(vec![2, 1, 1, 3]);
+	let q = into_bigrats(vec![1, 0]);
+	let d = into_bigrats(vec![1, 1]);
+
+	let z = lemke(m, q, d).unwrap();
+
+	assert_eq!(2, z.len());
+	assert!(z[0].is_zero());
+	assert!(z[1].is_zero());
+}
+
+#[test]
+fn lemke_ray_termination() {
+
+	// -z1 - 1 >= 0  has no solution with  z1 >= 0
+	let m = into_bigrats(vec![-1]);
+	let q = into_bigrats(vec![-1]);
+	let d = into_bigrats(vec![1]);
+
+	let ray = lemke(m, q, d).unwrap_err();
+
+	assert_eq!(1, ray.z.len());
+	assert!(ray.z[0].is_positive());
+	assert_eq!(ray.z[0], ray.z0);
+}
+
+#[test]
+fn lemke_rational_inputs() {
+
+	// same as lemke2 with the first row halved and the second row thirded
+	let m = vec![
+		Ratio::new(BigInt::from_i64(1).unwrap(), BigInt::from_i64(1).unwrap()),
+		Ratio::new(BigInt::from_i64(1).unwrap(), BigInt::from_i64(2).unwrap()),
+		Ratio::new(BigInt::from_i64(1).unwrap(), BigInt::from_i64(3).unwrap()),
+		Ratio::new(BigInt::from_i64(1).unwrap(), BigInt::from_i64(1).unwrap()),
+	];
+	let q = vec![
+		Ratio::new(BigInt::from_i64(-1).unwrap(), BigInt::from_i64(2).unwrap()),
+		Ratio::new(BigInt::from_i64(-1).unwrap(), BigInt::from_i64(3).unwrap()),
+	];
+	let d = into_bigrats(vec![1, 1]);
+
+	let z = lemke(m, q, d).unwrap();
+
+	assert_eq!(Ratio::new(BigInt::from_i64(2).unwrap(), BigInt::from_i64(5).unwrap()), z[0]);
+	assert_eq!(Ratio::new(BigInt::from_i64(1).unwrap(), BigInt::from_i64(5).unwrap()), z[1]);
+}
+
+#[test]
+fn lemke_records_stats() {
+
+	let m = into_bigrats(vec![0, -1, 2, 2, 0, -2, -1, 1, 0]);
+	let q = into_bigrats(vec![-3, 6, -1]);
+	let d = into_bigrats(vec![1, 1, 1]);
+
+	let mut lcp = LCP::new(m, q);
+	lcp.add_covering_vector(d);
+	lcp.solve(0).unwrap();
+
+	assert!(!lcp.stats.pivots.is_empty());
+	assert!(lcp.stats.pivots.iter().all(|p| p.candidates >= p.rhs_ties && p.rhs_ties >= 1));
+}
+
+#[test]
+fn lemke_resumes_after_pivot_limit() {
+
+	let m = into_bigrats(vec![0, -1, 2, 2, 0, -2, -1, 1, 0]);
+	let q = into_bigrats(vec![-3, 6, -1]);
+	let d = into_bigrats(vec![1, 1, 1]);
+
+	let mut lcp = LCP::new(m.clone(), q.clone());
+	lcp.add_covering_vector(d.clone());
+
+	match lcp.solve_within(&Budget::pivots(1)) {
+		Err(Unsolved::Interrupted(Interruption::PivotLimit)) => {},
+		_ => panic!("should stop after one pivot"),
+	}
+	assert_eq!(1, lcp.pivot_count);
+
+	let z = lcp.solve_within(&Budget::unlimited()).unwrap();
+	assert_eq!(lemke(m.clone(), q.clone(), d.clone()).unwrap(), z);
+
+	// no partial solution when the pivot limit is hit
+	match lemke_with_pivot_max(m, q, d, 1) {
+		Err(Unsolved::Interrupted(Interruption::PivotLimit)) => {},
+		_ => panic!("should stop after one pivot"),
+	}
+}
+
+#[test]
+fn lemke_cancellation() {
+
+	let m = into_bigrats(vec![2, 1, 1, 3]);
+	let q = into_bigrats(vec![-1, -1]);
+	let d = into_bigrats(vec![2, 1]);
+
+	let token = CancellationToken::new();
+	token.cancel();
+
+	let mut lcp = LCP::new(m.clone(), q.clone());
+	lcp.add_covering_vector(d.clone());
+
+	match lcp.solve_within(&Budget::unlimited().with_cancellation(token)) {
+		Err(Unsolved::Interrupted(Interruption::Cancelled)) => {},
+		_ => panic!("should be cancelled before the first pivot"),
+	}
+	assert_eq!(0, lcp.pivot_count);
+
+	let z = lcp.solve_within(&Budget::unlimited().with_time_max(Duration::from_secs(60))).unwrap();
+	assert_eq!(lemke(m, q, d).unwrap(), z);
+}
+
+#[test]
+fn lemke_normalized() {
+
+	let m = vec![
+		Ratio::new(BigInt::from_i64(4).unwrap(), BigInt::from_i64(3).unwrap()),
+		Ratio::new(BigInt::from_i64(-8).unwrap(), BigInt::from_i64(1).unwrap()),
+		Ratio::new(BigInt::from_i64(6).unwrap(), BigInt::from_i64(1).unwrap()),
+		Ratio::new(BigInt::from_i64(12).unwrap(), BigInt::from_i64(5).unwrap()),
+		Ratio::new(BigInt::from_i64(0).unwrap(), BigInt::from_i64(1).unwrap()),
+		Ratio::new(BigInt::from_i64(-6).unwrap(), BigInt::from_i64(1).unwrap()),
+		Ratio::new(BigInt::from_i64(-4).unwrap(), BigInt::from_i64(3).unwrap()),
+		Ratio::new(BigInt::from_i64(8).unwrap(), BigInt::from_i64(1).unwrap()),
+		Ratio::new(BigInt::from_i64(0).unwrap(), BigInt::from_i64(1).unwrap()),
+	];
+	let q = into_bigrats(vec![-6, 12, -2]);
+	let d = into_bigrats(vec![2, 2, 2]);
+
+	let mut plain = LCP::new(m.clone(), q.clone());
+	plain.add_covering_vector(d.clone());
+	let z = plain.solve(0).unwrap();
+
+	let mut normalized = LCP::new(m, q);
+	normalized.add_covering_vector(d);
+	normalized.normalize();
+	assert_eq!(z, normalized.solve(0).unwrap());
+
+	assert_eq!(plain.pivot_count, normalized.max_bits.len());
+	assert!(normalized.max_bits.iter().max() <= plain.max_bits.iter().max());
+}
+
+#[test]
+fn lcp_displays_tableau() {
+
+	let mut lcp = LCP::new(into_bigrats(vec![2, 1, 1, 3]), into_bigrats(vec![-1, -1]));
+	lcp.add_covering_vector(into_bigrats(vec![2, 1]));
+
+	assert_eq!(
+		"basis  z0  z1  z2  RHS\n   w1   2   2   1    1\n   w2   1   1   3    1\ndet = -1\nscale factors: z0 = 1, z1 = 1, z2 = 1, RHS = 1",
+		lcp.to_string());
+
+	lcp.solve(0).unwrap();
+	assert_eq!(
+		"basis  w2  z0  w1  RHS\n   z2  -2   0   1    1\n   z1   1   5  -3    2\ndet = 5\nscale factors: z0 = 1, z1 = 1, z2 = 1, RHS = 1",
+		lcp.to_string());
+}
+
+#[test]
+fn lcp_exports_rational_tableau() {
+
+	let m = into_bigrats(vec![2, 1, 1, 3]);
+	let q = into_bigrats(vec![-1, -1]);
+	let mut lcp = LCP::new(m.clone(), q.clone());
+	lcp.add_covering_vector(into_bigrats(vec![2, 1]));
+
+	// w = q + d z0 + M z  with all of  z0, z  cobasic
+	let rationals = lcp.tableau_rationals();
+	assert_eq!(into_bigrats(vec![-2, -2, -1, -1]), rationals[0]);
+	assert_eq!(into_bigrats(vec![-1, -1, -3, -1]), rationals[1]);
+
+	// after solving, the RHS is the value of the basic variable
+	let z = lcp.solve(0).unwrap();
+	let rationals = lcp.tableau_rationals();
+	for (row, values) in rationals.iter().enumerate() {
+		let var = lcp.vars.from_row(row);
+		if var.is_z() && !var.is_z0() {
+			assert!(z.contains(&values[lcp.vars.rhs_col()]));
+		}
+	}
+	assert_eq!(z[0], rationals[lcp.vars.to_row(&lcp.vars.z(1))][lcp.vars.rhs_col()]);
+	assert_eq!(z[1], rationals[lcp.vars.to_row(&lcp.vars.z(2))][lcp.vars.rhs_col()]);
+}
+
+#[test]
+fn lcp_shows_variable_names() {
+
+	let names = vec!["Raise".to_string(), "Fold".to_string()];
+	let mut lcp = LCP::with_names(into_bigrats(vec![2, 1, 1, 3]), into_bigrats(vec![-1, -1]), names);
+	lcp.add_covering_vector(into_bigrats(vec![2, 1]));
+
+	assert_eq!("Raise", lcp.vars.name(&lcp.vars.z(1)));
+	assert_eq!("slack(Fold)", lcp.vars.name(&lcp.vars.w(2)));
+	assert_eq!("z0", lcp.vars.name(&lcp.vars.z(0)));
+	assert!(lcp.to_string().starts_with("       basis  z0  Raise  Fold  RHS\nslack(Raise)   2      2     1    1\n"));
+
+	let trace = Rc::new(RefCell::new(Vec::new()));
+	let lines = trace.clone();
+	lcp.set_pivot_trace(move |count, enter, leave| lines.borrow_mut().push(format!("{}. entering: {}, leaving: {}", count, enter, leave)));
+
+	let z = lcp.solve(0).unwrap();
+	assert_eq!("Raise = 2/5\nFold = 1/5\n", lcp.format_solution(&z));
+	assert_eq!(vec!["1. entering: z0, leaving: slack(Fold)", "2. entering: Fold, leaving: slack(Raise)", "3. entering: Raise, leaving: z0"], *trace.borrow());
+}
+
+#[test]
+fn lcp_resumes_from_state() {
+
+	let m = into_bigrats(vec![0, -1, 2, 2, 0, -2, -1, 1, 0]);
+	let q = into_bigrats(vec![-3, 6, -1]);
+	let d = into_bigrats(vec![1, 1, 1]);
+
+	let mut uninterrupted = LCP::new(m.clone(), q.clone());
+	uninterrupted.add_covering_vector(d.clone());
+	let z = uninterrupted.solve(0).unwrap();
+
+	let mut lcp = LCP::new(m, q);
+	lcp.add_covering_vector(d);
+	assert_eq!(LCPPhase::Start, LCP::from_state(&lcp.state()).unwrap().state().phase);
+
+	match lcp.solve_within(&Budget::pivots(2)) {
+		Err(Unsolved::Interrupted(Interruption::PivotLimit)) => {},
+		_ => panic!("Lemke should stop after 2 pivots"),
+	}
+	let state = lcp.state();
+	assert_eq!(None, state.solution);
+
+	let mut resumed = LCP::from_state(&state).unwrap();
+	assert_eq!(z, resumed.solve(0).unwrap());
+	assert_eq!(uninterrupted.pivot_count, resumed.pivot_count);
+
+	let done = resumed.state();
+	assert_eq!(LCPPhase::Done, done.phase);
+	assert_eq!(Some(z.clone()), done.solution);
+	assert_eq!(z, LCP::from_state(&done).unwrap().solve(0).unwrap());
+}
+
+#[test]
+fn lcp_restores_ray_termination() {
+
+	let mut lcp = LCP::new(into_bigrats(vec![-1]), into_bigrats(vec![-1]));
+	lcp.add_covering_vector(into_bigrats(vec![1]));
+	let ray = ended_on_ray(lcp.solve(0));
+
+	let restored = ended_on_ray(LCP::from_state(&lcp.state()).unwrap().solve(0));
+	assert_eq!(ray.enter, restored.enter);
+	assert_eq!(ray.z, restored.z);
+	assert_eq!(ray.z0, restored.z0);
+}
+
+#[test]
+fn lcp_rejects_inconsistent_states() {
+
+	let mut lcp = LCP::new(into_bigrats(vec![0, -1, 2, 2, 0, -2, -1, 1, 0]), into_bigrats(vec![-3, 6, -1]));
+	lcp.add_covering_vector(into_bigrats(vec![1, 1, 1]));
+	lcp.solve_within(&Budget::pivots(2)).unwrap_err();
+	let state = lcp.state();
+
+	let rejects = |change: &dyn Fn(&mut LCPState), message: &str| {
+		let mut corrupt = state.clone();
+		change(&mut corrupt);
+		assert_eq!(message, LCP::from_state(&corrupt).err().unwrap_or_else(|| panic!("restored despite: {}", message)).message);
+	};
+	rejects(&|state| state.q.clear(), "q is empty");
+	rejects(&|state| { state.m.pop(); }, "M must be 3x3 but has 8 entries");
+	rejects(&|state| { state.d.pop(); }, "d must have 3 entries but has 2");
+	rejects(&|state| state.names = vec!["x".to_string()], "There must be 3 names but there are 1");
+	rejects(&|state| { state.basis.pop(); }, "The basis must have 3 variables but has 2");
+	rejects(&|state| state.basis[0] = "z9".to_string(), "Unknown LCP variable z9");
+	rejects(&|state| state.basis[0] = state.basis[1].clone(), &format!("{} is twice in the basis", state.basis[1]));
+	rejects(&|state| state.phase = LCPPhase::Pivoting { entering: "x".to_string() }, "Unknown LCP variable x");
+	rejects(&|state| state.phase = LCPPhase::Pivoting { entering: state.basis[0].clone() }, &format!("{} is already in the saved basis", state.basis[0]));
+	rejects(&|state| state.phase = LCPPhase::Ray { entering: state.basis[0].clone() }, &format!("{} is already in the saved basis", state.basis[0]));
+
+	// the columns of  z1  and  z2  are parallel
+	let mut singular = LCP::new(into_bigrats(vec![1, 1, 1, 1]), into_bigrats(vec![-1, -1]));
+	singular.add_covering_vector(into_bigrats(vec![1, 1]));
+	singular.solve_within(&Budget::pivots(1)).unwrap_err();
+	let mut state = singular.state();
+	state.basis = vec!["z1".to_string(), "z2".to_string()];
+	assert_eq!("The saved basis is singular", LCP::from_state(&state).err().unwrap().message);
+
+	// z1  has no positive entry to leave by, Lemke ended on a ray
+	let mut ray = LCP::new(into_bigrats(vec![-1]), into_bigrats(vec![-1]));
+	ray.add_covering_vector(into_bigrats(vec![1]));
+	ray.solve(0).unwrap_err();
+	let mut state = ray.state();
+	state.phase = LCPPhase::Pivoting { entering: "z1".to_string() };
+	assert_eq!("z1 cannot enter the saved basis", LCP::from_state(&state).err().unwrap().message);
+}
+
+#[test]
+fn lcp_traces_homotopy() {
+
+	let m = into_bigrats(vec![0, -1, 2, 2, 0, -2, -1, 1, 0]);
+	let q = into_bigrats(vec![-3, 6, -1]);
+	let d = into_bigrats(vec![1, 1, 1]);
+
+	let mut lcp = LCP::new(m.clone(), q.clone());
+	lcp.add_covering_vector(d.clone());
+	lcp.trace_homotopy();
+	let z = lcp.solve(0).unwrap();
+
+	let homotopy = lcp.homotopy().unwrap();
+	assert_eq!(lcp.pivot_count, homotopy.segments.len());
+	assert_eq!(Some(z.clone()), homotopy.segments.last().unwrap().point(&BigRational::zero()));
+
+	// consecutive segments meet at their vertices (z0  is constant on two of them here)
+	assert_eq!(2, homotopy.segments.iter().filter(|segment| segment.slope.is_none()).count());
+	for pair in homotopy.segments.windows(2).skip(1) {
+		let z0 = pair[0].z0_end.clone().unwrap();
+		assert_eq!(pair[1].z0_start, z0);
+		if pair[0].slope.is_some() {
+			assert_eq!(Some(pair[1].z_start.clone()), pair[0].point(&z0));
+		}
+	}
+
+	// every point is almost complementary:  w = q + d z0 + M z >= 0,  z >= 0
+	let two = BigRational::from_integer(BigInt::from_i64(2).unwrap());
+	for segment in homotopy.segments.iter().skip(1) {
+		let z0 = segment.z0_start.clone().add(segment.z0_end.clone().unwrap()).div(&two);
+		for point in homotopy.points(&z0) {
+			for i in 0..3 {
+				let w_i = (0..3).fold(q[i].clone().add(d[i].clone().mul(&z0)), |acc, j| acc.add(m[i*3 + j].clone().mul(&point[j])));
+				assert!(!w_i.is_negative());
+				assert!(!point[i].is_negative());
+			}
+		}
+	}
+
+	// far out on the primary ray  z = 0
+	assert_eq!(Some(vec![BigRational::zero(); 3]), homotopy.point(&BigRational::from_integer(BigInt::from_i64(100).unwrap())));
+}
+
+#[test]
+fn lcp_traces_homotopy_to_ray() {
+
+	let mut lcp = LCP::new(into_bigrats(vec![-1]), into_bigrats(vec![-1]));
+	lcp.add_covering_vector(into_bigrats(vec![1]));
+	lcp.trace_homotopy();
+	let ray = ended_on_ray(lcp.solve(0));
+
+	// the secondary ray is left open
+	let last = lcp.homotopy().unwrap().segments.last().unwrap();
+	assert_eq!(ray.enter, last.entering);
+	assert_eq!(None, last.z0_end);
+	assert_eq!(ray.z0, last.rate);
+}
@@ -0,0 +1,109 @@
+use num::rational::BigRational;
+
+#[cfg(feature = "serde")] use serde::{Serialize,Deserialize};
+
+#[cfg(all(test, feature = "serde"))] use serde_json;
+#[cfg(all(test, feature = "serde"))] use super::lcp::{LCP,into_bigrats};
+
+/*
+ * Plain snapshot of an  LCP  and how far Lemke got with it, see
+ * LCP::state  and  LCP::from_state.  Variables are given as  z{i}
+ * and  w{i}  (regardless of names), the basis in tableau row order.
+ * With the  serde  feature rationals are written as "p/q" strings.
+ */
+#[derive(Debug,Clone,PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LCPState {
+    #[cfg_attr(feature = "serde", serde(with = "rational_strings"))]
+    pub m: Vec<BigRational>,
+    #[cfg_attr(feature = "serde", serde(with = "rational_strings"))]
+    pub q: Vec<BigRational>,
+    #[cfg_attr(feature = "serde", serde(with = "rational_strings"))]
+    pub d: Vec<BigRational>,
+    pub names: Vec<String>,
+    pub normalized: bool,
+
+    pub basis: Vec<String>,
+    pub phase: LCPPhase,
+    pub pivot_count: usize,
+    #[cfg_attr(feature = "serde", serde(with = "optional_rational_strings"))]
+    pub solution: Option<Vec<BigRational>>,
+}
+
+#[derive(Debug,Clone,PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LCPPhase {
+    // Lemke has not started, the basis is  w
+    Start,
+    // interrupted before  entering  enters the basis
+    Pivoting { entering: String },
+    Done,
+    // ray termination when  entering  entered
+    Ray { entering: String },
+}
+
+// why  LCP::from_state  cannot restore a state
+#[derive(Debug,Clone,PartialEq)]
+pub struct LCPError {
+    pub message: String,
+}
+
+#[cfg(feature = "serde")]
+fn to_string(value: &BigRational) -> String {
+    format!("{}/{}", value.numer(), value.denom())
+}
+
+#[cfg(feature = "serde")]
+fn from_string<E: ::serde::de::Error>(value: &str) -> Result<BigRational, E> {
+    value.parse().map_err(|_| E::custom(format!("not a rational p/q: {}", value)))
+}
+
+#[cfg(feature = "serde")]
+mod rational_strings {
+    use num::rational::BigRational;
+    use serde::{Serialize,Serializer,Deserialize,Deserializer};
+
+    pub fn serialize<S: Serializer>(values: &[BigRational], serializer: S) -> Result<S::Ok, S::Error> {
+        values.iter().map(super::to_string).collect::<Vec<String>>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<BigRational>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?.iter().map(|value| super::from_string(value)).collect()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod optional_rational_strings {
+    use num::rational::BigRational;
+    use serde::{Serialize,Serializer,Deserialize,Deserializer};
+
+    pub fn serialize<S: Serializer>(values: &Option<Vec<BigRational>>, serializer: S) -> Result<S::Ok, S::Error> {
+        values.as_ref()
+            .map(|values| values.iter().map(super::to_string).collect::<Vec<String>>())
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<BigRational>>, D::Error> {
+        match Option::<Vec<String>>::deserialize(deserializer)? {
+            Some(values) => values.iter().map(|value| super::from_string(value)).collect::<Result<_, _>>().map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn lcp_state_round_trips_through_json() {
+
+    let mut lcp = LCP::with_names(into_bigrats(vec![2, 1, 1, 3]), into_bigrats(vec![-1, -1]), vec!["a".to_string(), "b".to_string()]);
+    lcp.add_covering_vector(into_bigrats(vec![2, 1]));
+    lcp.solve(0).unwrap();
+
+    let json = serde_json::to_string(&lcp.state()).unwrap();
+    assert!(json.contains("\"solution\":[\"2/5\",\"1/5\"]"));
+    assert!(json.contains("\"basis\":[\"z2\",\"z1\"]"));
+
+    let state: LCPState = serde_json::from_str(&json).unwrap();
+    assert_eq!(lcp.state(), state);
+    assert_eq!(lcp.state(), LCP::from_state(&state).unwrap().state());
+}
@@ -0,0 +1,166 @@
+use rand::{Rng,SeedableRng};
+use rand::rngs::StdRng;
+
+use super::tableau::TableauEngine;
+use super::tableau_vars::{TableauVariable,TableauVariables};
+use super::lex_min_ratio::{LexStats,PivotStats,lexminratio_with_stats,lexminratio_forcing_z0,leave_candidates,process_rhs};
+
+#[cfg(test)] use num::rational::BigRational;
+#[cfg(test)] use num::traits::{Zero,Signed};
+#[cfg(test)] use std::ops::{Add,Mul};
+#[cfg(test)] use super::lcp::{LCP,into_bigrats};
+
+/*
+ * Selects the leaving variable when  enter  enters the basis.
+ * Returns the leaving variable and whether  z0  is among the
+ * min ratio rows of the RHS (so that  z0  can leave), or None
+ * on ray termination.
+ *
+ * Only the lexicographic rule is guaranteed to terminate on
+ * degenerate problems; the others may cycle and exist for
+ * experimenting with pivot paths.
+ */
+pub trait LeavingRule {
+	fn leaving(&mut self, tableau: &dyn TableauEngine, vars: &TableauVariables, enter: &TableauVariable, stats: &mut LexStats) -> Option<(TableauVariable, bool)>;
+}
+
+/*
+ * lexicographic minimum ratio over the RHS and W columns (default)
+ */
+pub struct LexMinRatio;
+
+impl LeavingRule for LexMinRatio {
+	fn leaving(&mut self, tableau: &dyn TableauEngine, vars: &TableauVariables, enter: &TableauVariable, stats: &mut LexStats) -> Option<(TableauVariable, bool)> {
+		lexminratio_with_stats(tableau, vars, enter, stats)
+	}
+}
+
+/*
+ * lexicographic, but  z0  leaves whenever it ties in the RHS min ratio test
+ */
+pub struct PreferZ0;
+
+impl LeavingRule for PreferZ0 {
+	fn leaving(&mut self, tableau: &dyn TableauEngine, vars: &TableauVariables, enter: &TableauVariable, stats: &mut LexStats) -> Option<(TableauVariable, bool)> {
+		lexminratio_forcing_z0(tableau, vars, enter, stats, true)
+	}
+}
+
+/*
+ * Bland's rule: the least variable in  Z(0) .. Z(n) W(1) .. W(n)
+ * among the min ratio rows of the RHS
+ */
+pub struct LeastIndex;
+
+impl LeavingRule for LeastIndex {
+	fn leaving(&mut self, tableau: &dyn TableauEngine, vars: &TableauVariables, enter: &TableauVariable, stats: &mut LexStats) -> Option<(TableauVariable, bool)> {
+		let (ties, z0_can_leave) = rhs_ties(tableau, vars, enter, stats)?;
+		let leave = ties.into_iter().min().unwrap();
+		Some((leave, z0_can_leave))
+	}
+}
+
+/*
+ * uniformly random choice among the min ratio rows of the RHS,
+ * reproducible from  seed
+ */
+pub struct RandomTieBreak {
+	rng: StdRng,
+}
+
+impl RandomTieBreak {
+	pub fn new(seed: u64) -> RandomTieBreak {
+		RandomTieBreak { rng: StdRng::seed_from_u64(seed) }
+	}
+}
+
+impl LeavingRule for RandomTieBreak {
+	fn leaving(&mut self, tableau: &dyn TableauEngine, vars: &TableauVariables, enter: &TableauVariable, stats: &mut LexStats) -> Option<(TableauVariable, bool)> {
+		let (mut ties, z0_can_leave) = rhs_ties(tableau, vars, enter, stats)?;
+		let idx = self.rng.gen_range(0..ties.len());
+		Some((ties.swap_remove(idx), z0_can_leave))
+	}
+}
+
+/*
+ * basic variables of the min ratio rows of the RHS, no lexicographic
+ * tie-breaking, so  lex_columns  is always recorded as zero
+ */
+fn rhs_ties(tableau: &dyn TableauEngine, vars: &TableauVariables, enter: &TableauVariable, stats: &mut LexStats) -> Option<(Vec<TableauVariable>, bool)> {
+
+	let mut leave_candidate_rows = leave_candidates(tableau, vars, enter);
+	if leave_candidate_rows.is_empty() {
+		return None;  // ray termination
+	}
+
+	let candidates = leave_candidate_rows.len();
+	let z0_can_leave = process_rhs(tableau, vars, vars.to_col(enter), &mut leave_candidate_rows);
+
+	stats.pivots.push(PivotStats {
+		candidates,
+		rhs_ties: leave_candidate_rows.len(),
+		lex_columns: 0,
+		z0_tied: z0_can_leave && leave_candidate_rows.len() > 1,
+	});
+
+	let ties = leave_candidate_rows.into_iter().map(|row| vars.from_row(row)).collect();
+	Some((ties, z0_can_leave))
+}
+
+#[cfg(test)]
+fn assert_lcp_solution(m: &[BigRational], q: &[BigRational], z: &[BigRational]) {
+	let n = q.len();
+	for i in 0..n {
+		let w_i = (0..n).fold(q[i].clone(), |acc, j| acc.add(m[i*n + j].clone().mul(&z[j])));
+		assert!(!z[i].is_negative());
+		assert!(!w_i.is_negative());
+		assert!(z[i].is_zero() || w_i.is_zero());
+	}
+}
+
+#[cfg(test)]
+fn solve_with<R: LeavingRule + 'static>(m: &[BigRational], q: &[BigRational], d: &[BigRational], rule: R) -> Vec<BigRational> {
+	let mut lcp = LCP::new(m.to_vec(), q.to_vec());
+	lcp.add_covering_vector(d.to_vec());
+	lcp.set_leaving_rule(rule);
+	lcp.solve(0).unwrap()
+}
+
+#[test]
+fn leaving_rules_solve_lcp() {
+
+	let m = into_bigrats(vec![0, -1, 2, 2, 0, -2, -1, 1, 0]);
+	let q = into_bigrats(vec![-3, 6, -1]);
+	let d = into_bigrats(vec![1, 1, 1]);
+
+	assert_lcp_solution(&m, &q, &solve_with(&m, &q, &d, LexMinRatio));
+	assert_lcp_solution(&m, &q, &solve_with(&m, &q, &d, PreferZ0));
+	assert_lcp_solution(&m, &q, &solve_with(&m, &q, &d, LeastIndex));
+	assert_lcp_solution(&m, &q, &solve_with(&m, &q, &d, RandomTieBreak::new(7)));
+}
+
+#[test]
+fn leaving_rules_solve_degenerate_lcp() {
+
+	// equal ratios  q[i]/d[i]  in the first pivot
+	let m = into_bigrats(vec![2, 1, 1, 1, 2, 1, 1, 1, 2]);
+	let q = into_bigrats(vec![-1, -1, -1]);
+	let d = into_bigrats(vec![1, 1, 1]);
+
+	assert_lcp_solution(&m, &q, &solve_with(&m, &q, &d, LexMinRatio));
+	assert_lcp_solution(&m, &q, &solve_with(&m, &q, &d, PreferZ0));
+	assert_lcp_solution(&m, &q, &solve_with(&m, &q, &d, LeastIndex));
+	for seed in 0..5 {
+		assert_lcp_solution(&m, &q, &solve_with(&m, &q, &d, RandomTieBreak::new(seed)));
+	}
+}
+
+#[test]
+fn random_tie_break_is_reproducible() {
+
+	let m = into_bigrats(vec![2, 1, 1, 1, 2, 1, 1, 1, 2]);
+	let q = into_bigrats(vec![-1, -1, -1]);
+	let d = into_bigrats(vec![1, 1, 1]);
+
+	assert_eq!(solve_with(&m, &q, &d, RandomTieBreak::new(42)), solve_with(&m, &q, &d, RandomTieBreak::new(42)));
+}
@@ -1,4 +1,4 @@
-use super::tableau::Tableau;
+use super::tableau::TableauEngine;
 use super::tableau_vars::{TableauVariable,TableauVariables};
 
 use std::cmp::Ordering;
@@ -6,6 +6,57 @@ use num::traits::Signed;
 
 #[cfg(test)] use num::bigint::BigInt;
 #[cfg(test)] use num::traits::FromPrimitive;
+#[cfg(test)] use super::tableau::Tableau;
+
+/*
+ * statistics of one lex-minratio test:
+ * candidates   rows with positive entry in the entering column
+ * rhs_ties     candidates left after the min ratio test on the RHS
+ * lex_columns  number of W columns tested to break remaining ties
+ * z0_tied      z0  among the rhs_ties when there is more than one
+ */
+#[derive(Debug,Clone,PartialEq)]
+pub struct PivotStats {
+	pub candidates: usize,
+	pub rhs_ties: usize,
+	pub lex_columns: usize,
+	pub z0_tied: bool,
+}
+
+/*
+ * records one  PivotStats  per lex-minratio test
+ * (i.e. per pivot in the Lemke loop)
+ */
+#[derive(Debug,Clone,Default)]
+pub struct LexStats {
+	pub pivots: Vec<PivotStats>,
+}
+
+impl LexStats {
+
+	pub fn new() -> LexStats {
+		LexStats { pivots: Vec::new() }
+	}
+
+	fn record(&mut self, stats: PivotStats) {
+		self.pivots.push(stats);
+	}
+
+	// pivots where the RHS min ratio test alone did not determine the leaving variable
+	pub fn degenerate_pivots(&self) -> usize {
+		self.pivots.iter().filter(|p| p.rhs_ties > 1).count()
+	}
+
+	// how often lex column  j  (W(j), index  j-1) was the last one needed to break ties
+	pub fn lex_column_counts(&self) -> Vec<usize> {
+		let max = self.pivots.iter().map(|p| p.lex_columns).max().unwrap_or(0);
+		let mut counts = vec![0; max];
+		for p in self.pivots.iter().filter(|p| p.lex_columns > 0) {
+			counts[p.lex_columns - 1] += 1;
+		}
+		counts
+	}
+}
 
 /*
  * minVar
@@ -16,36 +67,66 @@ use num::traits::Signed;
  * boolean  *z0leave  indicates back that  z0  can leave the
  * basis, but the lex-minratio test is performed fully,
  * so the returned value might not be the index of  z0
+ * returns None on ray termination, i.e. when no entry of the
+ * entering column is positive
  */
-pub fn lexminratio(tableau: &Tableau, vars: &TableauVariables, enter: &TableauVariable) -> (TableauVariable, bool) {
+pub fn lexminratio(tableau: &dyn TableauEngine, vars: &TableauVariables, enter: &TableauVariable) -> Option<(TableauVariable, bool)> {
+	lexminratio_with_stats(tableau, vars, enter, &mut LexStats::new())
+}
 
-	let mut leave_candidate_rows = Vec::new();
+/*
+ * as  lexminratio, recording the tie-breaking statistics in  stats
+ * (nothing is recorded on ray termination)
+ */
+pub fn lexminratio_with_stats(tableau: &dyn TableauEngine, vars: &TableauVariables, enter: &TableauVariable, stats: &mut LexStats) -> Option<(TableauVariable, bool)> {
+	lexminratio_forcing_z0(tableau, vars, enter, stats, false)
+}
+
+/*
+ * as  lexminratio_with_stats, but if  force_z0  and  z0  is among the
+ * min ratio rows of the RHS then  z0  leaves without further tests
+ */
+pub fn lexminratio_forcing_z0(tableau: &dyn TableauEngine, vars: &TableauVariables, enter: &TableauVariable, stats: &mut LexStats, force_z0: bool) -> Option<(TableauVariable, bool)> {
+
+	let enter_col = vars.to_col(enter);
+	let mut leave_candidate_rows = leave_candidates(tableau, vars, enter);
+
+	if leave_candidate_rows.len() == 0 {
+		return None;  // ray termination
+	}
+
+	let mut pivot_stats = PivotStats {
+		candidates: leave_candidate_rows.len(),
+		rhs_ties: 0,
+		lex_columns: 0,
+		z0_tied: false,
+	};
+
+	let z0_can_leave = process_candidates(tableau, vars, enter_col, &mut leave_candidate_rows, &mut pivot_stats, force_z0);
+	stats.record(pivot_stats);
+
+	Some((vars.from_row(leave_candidate_rows[0]), z0_can_leave))
+}
+
+/*
+ * leavecand = { i | A[i][col] > 0 }  for the entering column
+ * empty on ray termination
+ */
+pub fn leave_candidates(tableau: &dyn TableauEngine, vars: &TableauVariables, enter: &TableauVariable) -> Vec<usize> {
 
 	if vars.is_basic(&enter) {
-		panic!("Variable {} is already in basis. Must be cobasic to enter.", enter.to_string());
+		panic!("Variable {} is already in basis. Must be cobasic to enter.", vars.name(enter));
 	}
 
 	let enter_col = vars.to_col(enter);
 
-	// start with  leavecand = { i | A[i][col] > 0 }
+	let mut leave_candidate_rows = Vec::new();
 	for i in 0..vars.n {
 		if tableau.entry(i, enter_col).is_positive() {
 			leave_candidate_rows.push(i);
 		}
 	}
-
-	if leave_candidate_rows.len() == 0 {
-		panic!("Ray termination when trying to enter {}", enter.to_string());  // convert to Result from panic...
-	}
-
-	/*else if (numcand == 1) {
-		RecordStats(0, numcand);
-		z0leave = IsLeavingRowZ0(leavecand[0]);
-	}*/
-
-	let z0_can_leave = process_candidates(tableau, vars, enter_col, &mut leave_candidate_rows);
-
-	(vars.from_row(leave_candidate_rows[0]), z0_can_leave)
+	leave_candidate_rows
 }
 
 /*
@@ -56,9 +137,18 @@ pub fn lexminratio(tableau: &Tableau, vars: &TableauVariables, enter: &TableauVa
  * in the tableau.  That test has an easy known result if
  * the test column is basic or equal to the entering variable.
  */
-fn process_candidates(tableau: &Tableau, vars: &TableauVariables, enter_col: usize, leave_candidate_rows: &mut Vec<usize>) -> bool {
+fn process_candidates(tableau: &dyn TableauEngine, vars: &TableauVariables, enter_col: usize, leave_candidate_rows: &mut Vec<usize>, stats: &mut PivotStats, force_z0: bool) -> bool {
 
 	let z0_can_leave = process_rhs(tableau, vars, enter_col, leave_candidate_rows);
+	stats.rhs_ties = leave_candidate_rows.len();
+	stats.z0_tied = z0_can_leave && stats.rhs_ties > 1;
+
+	if z0_can_leave && force_z0 {
+		let z0_row = vars.to_row(&vars.z(0));
+		leave_candidate_rows.clear();
+		leave_candidate_rows.push(z0_row);
+	}
+
     let mut j = 1;
 	while leave_candidate_rows.len() > 1 {
 		//if j >= A.RHS() {                                             /* impossible, perturbed RHS should have full rank */
@@ -74,27 +164,21 @@ fn process_candidates(tableau: &Tableau, vars: &TableauVariables, enter_col: usi
 				take_min_ratio_rows(tableau, enter_col, test_col, leave_candidate_rows);
 			}
 		}
+		stats.lex_columns = j;
         j += 1;
 	}
 
 	z0_can_leave
 }
 
+// W(j)  basic:  only its own row has a nonzero entry in its unit column
 fn remove_row(leave_candidate_rows: &mut Vec<usize>, row_to_rm: usize) {
-	let rm_idx = {
-		let mut idx = 0;
-		for row in leave_candidate_rows.iter() {
-			if *row == row_to_rm {
-				break;
-			}
-			idx += 1;
-		}
-		idx
-	};
-	leave_candidate_rows.swap_remove(rm_idx);
+	if let Some(rm_idx) = leave_candidate_rows.iter().position(|row| *row == row_to_rm) {
+		leave_candidate_rows.swap_remove(rm_idx);
+	}
 }
 
-fn process_rhs(tableau: &Tableau, vars: &TableauVariables, enter_col: usize, leave_candidate_rows: &mut Vec<usize>) -> bool {
+pub fn process_rhs(tableau: &dyn TableauEngine, vars: &TableauVariables, enter_col: usize, leave_candidate_rows: &mut Vec<usize>) -> bool {
 
 	take_min_ratio_rows(tableau, enter_col, vars.rhs_col(), leave_candidate_rows);
 
@@ -106,14 +190,14 @@ fn process_rhs(tableau: &Tableau, vars: &TableauVariables, enter_col: usize, lea
 			break;
 		}
 		/* alternative, to force z0 leaving the basis:
-		 * return whichvar[leavecand[i]];
+		 * see  lexminratio_forcing_z0
 		 */
 	}
 
 	z0_can_leave
 }
 
-fn take_min_ratio_rows(tableau: &Tableau, enter_col: usize, test_col: usize, leave_candidate_rows: &mut Vec<usize>) {
+fn take_min_ratio_rows(tableau: &dyn TableauEngine, enter_col: usize, test_col: usize, leave_candidate_rows: &mut Vec<usize>) {
 
 	let mut num_min_candidates = 0;
 	for i in 1..leave_candidate_rows.len() {  // investigate remaining candidates
@@ -192,19 +276,22 @@ fn lexminvar_works() {
 	a.set(1, 2, BigInt::from_i32(3).unwrap());
 	a.set(1, 3, BigInt::from_i32(-1).unwrap());
 
-	let (leave, z0_can_leave) = lexminratio(&a, &vars, &vars.z(0));
+	let (leave, z0_can_leave) = lexminratio(&a, &vars, &vars.z(0)).unwrap();
     assert_eq!(vars.w(2), leave);     // w2 = 4 is leaving
 	assert_eq!(false, z0_can_leave);  // z0 can not leave
 
-	let (leave, z0_can_leave) = lexminratio(&a, &vars, &vars.z(1));
+	let (leave, z0_can_leave) = lexminratio(&a, &vars, &vars.z(1)).unwrap();
     assert_eq!(vars.w(2), leave);     // w2 = 4 is leaving
 	assert_eq!(false, z0_can_leave);  // z0 can not leave
 
-	let (leave, z0_can_leave) = lexminratio(&a, &vars, &vars.z(2));
+	let (leave, z0_can_leave) = lexminratio(&a, &vars, &vars.z(2)).unwrap();
 	assert_eq!(vars.w(1), leave);     // w1 = 3 is leaving
     assert_eq!(false, z0_can_leave);  // z0 can not leave
 
-    // TODO: this plus ray termination...
+	a.negate_col(1);
+	assert_eq!(None, lexminratio(&a, &vars, &vars.z(1)));  // ray termination
+
+    // TODO: this...
 	//_, _, err = lexminratio(lcp, lcp.w(1))
 	//assert.NotNil(t, err, "Should be an error")
 	//assert.Equal(t, "Variable w1 is already in basis. Must be cobasic to enter.", err.Error())
@@ -214,6 +301,48 @@ fn lexminvar_works() {
 	//assert.Equal(t, "Variable w2 is already in basis. Must be cobasic to enter.", err.Error())
 }
 
+#[test]
+fn lexminratio_records_stats() {
+
+    let n = 2;
+	let mut vars = TableauVariables::new(n);
+
+    let mut a = Tableau::new(n);
+	a.set(0, 0, BigInt::from_i32(1).unwrap());
+	a.set(0, 1, BigInt::from_i32(2).unwrap());
+	a.set(0, 2, BigInt::from_i32(1).unwrap());
+    a.set(0, 3, BigInt::from_i32(-1).unwrap());
+	a.set(1, 0, BigInt::from_i32(1).unwrap());
+	a.set(1, 1, BigInt::from_i32(1).unwrap());
+	a.set(1, 2, BigInt::from_i32(3).unwrap());
+	a.set(1, 3, BigInt::from_i32(-1).unwrap());
+
+	let mut stats = LexStats::new();
+
+	// z1 column: RHS ratios -1/2 and -1/1 differ, no ties
+	lexminratio_with_stats(&a, &vars, &vars.z(1), &mut stats).unwrap();
+	assert_eq!(PivotStats { candidates: 2, rhs_ties: 1, lex_columns: 0, z0_tied: false }, stats.pivots[0]);
+
+	// z0 column: RHS ratios tie, basic W(1) decides
+	let (leave, _) = lexminratio_with_stats(&a, &vars, &vars.z(0), &mut stats).unwrap();
+	assert_eq!(vars.w(2), leave);
+	assert_eq!(PivotStats { candidates: 2, rhs_ties: 2, lex_columns: 1, z0_tied: false }, stats.pivots[1]);
+
+	// bring z0 into the basis and make its row tie with w2 for entering z1
+	let (w1, z0) = (vars.w(1), vars.z(0));
+	vars.pivot(&mut a, &w1, &z0);
+	a.set(1, 1, BigInt::from_i32(2).unwrap());
+	a.set(1, 3, BigInt::from_i32(-1).unwrap());
+
+	let (leave, z0_can_leave) = lexminratio_with_stats(&a, &vars, &vars.z(1), &mut stats).unwrap();
+	assert_eq!(vars.z(0), leave);
+	assert!(z0_can_leave);
+	assert_eq!(PivotStats { candidates: 2, rhs_ties: 2, lex_columns: 2, z0_tied: true }, stats.pivots[2]);
+
+	assert_eq!(2, stats.degenerate_pivots());
+	assert_eq!(vec![1, 1], stats.lex_column_counts());
+}
+
 /*
 #[test]
 fn lexninvar_on_large_tableau_works() {
@@ -238,13 +367,13 @@ fn lexninvar_on_large_tableau_works() {
 		}
 	}
 
-	let start = time::now();
+	let start = ::std::time::Instant::now();
 	for i in 0..1000 {
 		let (leave, z0_can_leave) = lexminratio(&a, &vars, &vars.z(0));
 		assert_eq!(vars.w(1), leave);
 		assert_eq!(false, z0_can_leave);
 	}
 
-	panic!("1000 lexmin took: {}", time::now() - start);
+	panic!("1000 lexmin took: {:?}", start.elapsed());
 }
 */
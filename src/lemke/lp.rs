@@ -0,0 +1,173 @@
+use num::rational::BigRational;
+use num::traits::{Zero,One,Signed};
+
+use std::ops::{Add,Mul,Neg};
+
+use super::lcp::{lemke,RayTermination};
+
+#[cfg(test)] use num::bigint::BigInt;
+#[cfg(test)] use num::rational::Ratio;
+#[cfg(test)] use num::traits::FromPrimitive;
+#[cfg(test)] use super::lcp::into_bigrats;
+
+// LP (aka. Linear Program)
+// =============================================================================
+// max c'x
+// s.t. Ax <= b
+//      x >= 0
+//
+// with dual  min b'y  s.t.  A'y >= c,  y >= 0.
+// Both are solved at once as the LCP with skew-symmetric
+//
+//     M = [  0  A' ]    q = [ -c ]    z = [ x ]
+//         [ -A  0  ]        [  b ]        [ y ]
+//
+// where  w = Mz + q >= 0  are the dual and primal slacks.  M is copositive-plus,
+// so Lemke either finds an optimal pair or ends in a ray  (x_h, y_h) >= 0  with
+// A x_h <= 0, A'y_h >= 0  and  c'x_h > b'y_h.
+
+pub enum LPResult {
+    // x  and  y  are optimal for the primal and dual, value = c'x = b'y
+    Optimal { x: Vec<BigRational>, y: Vec<BigRational>, value: BigRational },
+    // Farkas certificate:  y >= 0,  A'y >= 0,  b'y < 0
+    Infeasible { y: Vec<BigRational> },
+    // x  is feasible and  ray >= 0,  A ray <= 0,  c'ray > 0
+    Unbounded { x: Vec<BigRational>, ray: Vec<BigRational> },
+}
+
+/*
+ * A  is the  m x n  constraint matrix (row-major), b  has  m  entries
+ * and  c  has  n  entries
+ */
+pub fn maximize(a: Vec<BigRational>, b: Vec<BigRational>, c: Vec<BigRational>) -> LPResult {
+
+    let m = b.len();
+    let n = c.len();
+    if a.len() != m * n {
+        panic!("A must be {}x{} but has {} entries", m, n, a.len());
+    }
+
+    match solve_lcp(&a, &b, &c) {
+        Ok(z) => {
+            let x = z[..n].to_vec();
+            let y = z[n..].to_vec();
+            let value = dot(&c, &x);
+            LPResult::Optimal { x, y, value }
+        },
+        Err(ray) => {
+            let y_h = ray.z[n..].to_vec();
+            if dot(&b, &y_h).is_negative() {
+                return LPResult::Infeasible { y: y_h };
+            }
+
+            // c'x_h > 0:  the dual is infeasible, so the primal is either
+            // unbounded or infeasible.  Zero objective decides which one.
+            let x_h = ray.z[..n].to_vec();
+            match solve_lcp(&a, &b, &vec![BigRational::zero(); n]) {
+                Ok(z) => LPResult::Unbounded { x: z[..n].to_vec(), ray: x_h },
+                Err(ray) => LPResult::Infeasible { y: ray.z[n..].to_vec() },
+            }
+        }
+    }
+}
+
+fn solve_lcp(a: &[BigRational], b: &[BigRational], c: &[BigRational]) -> Result<Vec<BigRational>, RayTermination> {
+
+    let m = b.len();
+    let n = c.len();
+    let size = n + m;
+
+    let mut lcp_m = vec![BigRational::zero(); size * size];
+    for i in 0..m {
+        for j in 0..n {
+            let a_ij = &a[i*n + j];
+            lcp_m[j*size + (n+i)] = a_ij.clone();  // A'
+            lcp_m[(n+i)*size + j] = a_ij.neg();    // -A
+        }
+    }
+
+    let mut q = Vec::with_capacity(size);
+    q.extend(c.iter().map(|c_j| c_j.neg()));
+    q.extend(b.iter().cloned());
+
+    let d = vec![BigRational::one(); size];
+
+    lemke(lcp_m, q, d)
+}
+
+fn dot(u: &[BigRational], v: &[BigRational]) -> BigRational {
+    u.iter().zip(v.iter()).fold(BigRational::zero(), |acc, (u_i, v_i)| acc.add(u_i.mul(v_i)))
+}
+
+#[test]
+fn maximize_finds_optimum() {
+
+    // max 3x1 + 2x2  s.t.  x1 + x2 <= 4,  x1 + 3x2 <= 9,  x1 <= 3
+    let a = into_bigrats(vec![1, 1, 1, 3, 1, 0]);
+    let b = into_bigrats(vec![4, 9, 3]);
+    let c = into_bigrats(vec![3, 2]);
+
+    match maximize(a, b, c) {
+        LPResult::Optimal { x, y, value } => {
+            assert_eq!(into_bigrats(vec![3, 1]), x);
+            assert_eq!(into_bigrats(vec![2, 0, 1]), y);
+            assert_eq!(into_bigrats(vec![11]), vec![value]);
+        },
+        _ => panic!("LP should have an optimal solution"),
+    }
+}
+
+#[test]
+fn maximize_with_rationals() {
+
+    // max x1 + x2  s.t.  x1/2 + x2 <= 1,  x1 + x2/3 <= 1
+    let half = Ratio::new(BigInt::from_i64(1).unwrap(), BigInt::from_i64(2).unwrap());
+    let third = Ratio::new(BigInt::from_i64(1).unwrap(), BigInt::from_i64(3).unwrap());
+    let a = vec![half, BigRational::one(), BigRational::one(), third];
+    let b = into_bigrats(vec![1, 1]);
+    let c = into_bigrats(vec![1, 1]);
+
+    match maximize(a, b, c) {
+        LPResult::Optimal { x, value, .. } => {
+            let four_fifths = Ratio::new(BigInt::from_i64(4).unwrap(), BigInt::from_i64(5).unwrap());
+            let three_fifths = Ratio::new(BigInt::from_i64(3).unwrap(), BigInt::from_i64(5).unwrap());
+            assert_eq!(vec![four_fifths, three_fifths], x);
+            assert_eq!(Ratio::new(BigInt::from_i64(7).unwrap(), BigInt::from_i64(5).unwrap()), value);
+        },
+        _ => panic!("LP should have an optimal solution"),
+    }
+}
+
+#[test]
+fn maximize_detects_infeasibility() {
+
+    // max x1  s.t.  x1 <= -1
+    let a = into_bigrats(vec![1]);
+    let b = into_bigrats(vec![-1]);
+    let c = into_bigrats(vec![1]);
+
+    match maximize(a, b, c) {
+        LPResult::Infeasible { y } => {
+            assert!(y[0].is_positive());  // b'y < 0  with  A'y >= 0
+        },
+        _ => panic!("LP should be infeasible"),
+    }
+}
+
+#[test]
+fn maximize_detects_unboundedness() {
+
+    // max x1  s.t.  x1 - x2 <= 1
+    let a = into_bigrats(vec![1, -1]);
+    let b = into_bigrats(vec![1]);
+    let c = into_bigrats(vec![1, 0]);
+
+    match maximize(a, b, c) {
+        LPResult::Unbounded { x, ray } => {
+            assert!(x[0].clone() - x[1].clone() <= BigRational::one());
+            assert!(ray[0].is_positive());
+            assert!(ray[0] <= ray[1]);
+        },
+        _ => panic!("LP should be unbounded"),
+    }
+}
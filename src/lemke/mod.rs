@@ -1,6 +1,25 @@
+mod budget;
+mod homotopy;
+mod integer;
 mod lcp;
+mod lcp_state;
+mod leaving_rule;
 mod lex_min_ratio;
+#[cfg(feature = "multimodular")] mod modular_tableau;
 mod tableau;
 mod tableau_vars;
+pub mod lp;
 
-pub use self::lcp::LCP;
+pub use self::budget::{Budget,CancellationToken,Interruption};
+pub use self::homotopy::{Homotopy,HomotopySegment};
+pub use self::lcp::{LCP,RayTermination,Unsolved,lemke,lemke_with_pivot_max};
+pub use self::lcp_state::{LCPState,LCPPhase,LCPError};
+pub use self::leaving_rule::{LeavingRule,LexMinRatio,PreferZ0,LeastIndex,RandomTieBreak};
+pub use self::lex_min_ratio::{LexStats,PivotStats,lexminratio,lexminratio_with_stats};
+pub use self::integer::TableauInt;
+pub use self::tableau::{IntTableau,Tableau,TableauEngine,default_tableau};
+#[cfg(feature = "gmp")] pub use self::tableau::GmpTableau;
+#[cfg(feature = "parallel")] pub use self::tableau::PARALLEL_THRESHOLD;
+#[cfg(feature = "multimodular")] pub use self::modular_tableau::ModularTableau;
+pub use self::tableau_vars::{TableauVariable,TableauVariables};
+#[cfg(test)] pub use self::lcp::{into_bigrats,rat};
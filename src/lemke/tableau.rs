@@ -1,72 +1,176 @@
 use num::bigint::BigInt;
-use num::traits::{Zero,Signed,FromPrimitive};
 
-use std::ops::{Neg,Mul,Sub,Add,Div};
+use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::vec::Vec;
 
-pub struct Tableau {
-    values: Vec<BigInt>,
+use super::integer::TableauInt;
+
+#[cfg(feature = "parallel")] use rayon::prelude::*;
+
+#[cfg(test)] use num::traits::FromPrimitive;
+#[cfg(test)] use std::ops::Mul;
+
+/*
+ * The integer tableau operations Lemke's algorithm needs, so that
+ * the pivoting arithmetic can be swapped out (see  ModularTableau).
+ * Entries are returned owned where an engine has to reconstruct them.
+ */
+pub trait TableauEngine {
+    fn nrows(&self) -> usize;
+    fn ncols(&self) -> usize;
+    fn set(&mut self, row: usize, col: usize, value: BigInt);
+    fn entry(&self, row: usize, col: usize) -> Cow<'_, BigInt>;
+    fn determinant(&self) -> Cow<'_, BigInt>;
+    fn row_determinant(&self, row: usize) -> Cow<'_, BigInt>;
+    fn pivot(&mut self, row: usize, col: usize);
+    fn negate_col(&mut self, col: usize);
+    fn ratio_test(&self, rowa: usize, rowb: usize, cola: usize, colb: usize) -> Ordering;
+    fn normalize_rows(&mut self);
+    // None  if the engine does not hold the entries as big integers
+    fn max_bits(&self) -> Option<u64>;
+}
+
+// fraction-free tableau over any  TableauInt
+#[derive(Clone)]
+pub struct IntTableau<T> {
+    values: Vec<T>,
     pub ncols: usize,
     pub nrows: usize,
-    pub determinant: BigInt,
+    pub determinant: T,
+
+    /* when rows are normalized by their gcd, the coefficient of
+     * the basic variable of each row, otherwise  determinant  for all
+     */
+    row_dets: Option<Vec<T>>,
+
+    #[cfg(feature = "parallel")]
+    parallel_threshold: usize,
+}
+
+// with the  parallel  feature, tableaus with at least this many entries pivot on several threads
+#[cfg(feature = "parallel")]
+pub const PARALLEL_THRESHOLD: usize = 20_000;
+
+pub type Tableau = IntTableau<BigInt>;
+
+#[cfg(feature = "gmp")]
+pub type GmpTableau = IntTableau<::rug::Integer>;
+
+/*
+ * the engine an  LCP  pivots with unless told otherwise:
+ * GMP integers with the  gmp  feature,  num's  BigInt  without
+ */
+pub fn default_tableau(n: usize) -> Box<dyn TableauEngine> {
+    #[cfg(feature = "gmp")]
+    return Box::new(GmpTableau::new(n));
+    #[cfg(not(feature = "gmp"))]
+    return Box::new(Tableau::new(n));
 }
 
-impl Tableau {
-    pub fn new(n: usize) -> Tableau {
-        Tableau {
-            values: vec![BigInt::zero(); (n + 2) * n],
-            ncols: n + 2,
-            nrows: n,
-            determinant: BigInt::from_i32(-1).unwrap(),
+impl<T: TableauInt> IntTableau<T> {
+    // the LCP tableau for  -Iw + dz0 + Mz = -q,  see  LCP
+    pub fn new(n: usize) -> IntTableau<T> {
+        let mut tableau = IntTableau::with_dimensions(n, n + 2);
+        tableau.determinant = T::from_bigint(BigInt::from(-1));
+        tableau
+    }
+
+    // zero tableau with the identity as the coefficients of the basic variables
+    pub fn with_dimensions(nrows: usize, ncols: usize) -> IntTableau<T> {
+        IntTableau {
+            values: vec![T::from_bigint(BigInt::from(0)); ncols * nrows],
+            ncols,
+            nrows,
+            determinant: T::from_bigint(BigInt::from(1)),
+            row_dets: None,
+            #[cfg(feature = "parallel")]
+            parallel_threshold: PARALLEL_THRESHOLD,
+        }
+    }
+
+    /*
+     * from now on divide every row by the gcd of its entries after
+     * each pivot.  Rows then no longer share the common denominator
+     * determinant, see  row_determinant.
+     */
+    pub fn normalize_rows(&mut self) {
+        if self.row_dets.is_none() {
+            self.row_dets = Some(vec![self.determinant.clone(); self.nrows]);
+        }
+    }
+
+    pub fn is_normalized(&self) -> bool {
+        self.row_dets.is_some()
+    }
+
+    // coefficient of the basic variable in  row,  rhs[row] / row_determinant  is its value
+    pub fn row_determinant(&self, row: usize) -> &T {
+        match self.row_dets {
+            Some(ref row_dets) => &row_dets[row],
+            None => &self.determinant,
         }
     }
 
-    pub fn set(&mut self, row: usize, col: usize, value: BigInt) {
+    // largest bit length of any entry
+    pub fn max_bits(&self) -> u64 {
+        self.values.iter().map(|value| value.bits()).max().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: T) {
         self.values[row * self.ncols + col] = value;
     }
 
-    pub fn entry(&self, row: usize, col: usize) -> &BigInt {
+    pub fn entry(&self, row: usize, col: usize) -> &T {
         &self.values[row * self.ncols + col]
     }
 
     pub fn pivot(&mut self, row: usize, col: usize) {
 
+        if self.row_dets.is_some() {
+            return self.pivot_normalized(row, col);
+        }
+
     	let (entry_row_col_abs, negpivot) = {
             let entry_row_col = self.entry(row, col);
             if entry_row_col.is_zero() {
                 panic!("Trying to pivot on a zero");
             }
-            (entry_row_col.abs(), entry_row_col.is_negative())
+            (entry_row_col.abs_value(), entry_row_col.is_negative())
         }; /* pivelt anyhow later new determinant  */
 
         let cur_det = self.determinant.clone();
-    	for i in 0..self.nrows {
-    		if i != row {  // A[row][..] remains unchanged
-                let nonzero = !self.entry(i, col).is_zero();
-    			for j in 0..self.ncols {
-    				if j != col {  // A[..][col] remains unchanged
-
-    					//A[i,j] = (A[i,j] A[row,col] - A[i,col] A[row,j]) / det
-    					let mut tmp1 = self.entry(i, j).mul(&entry_row_col_abs);
-    					if nonzero {
-    						let tmp2 = self.entry(row, j).mul(self.entry(i, col));
-    						tmp1 = if negpivot {
-    							tmp1.add(tmp2)
-    						} else {
-    							tmp1.sub(tmp2)
-    						};
-    					}
-    					self.set(i, j, tmp1.div(&cur_det));
-    				}
-    			}
-    			if nonzero && !negpivot {
-    				// row  i  has been dealt with, update  A[i][col] safely
-                    let neg_entry = self.entry(i, col).neg();
-    				self.set(i, col, neg_entry);
-    			}
-    		}
-    	}
+        let ncols = self.ncols;
+        let parallel = self.is_parallel();
+        let pivot_row = self.values[row * ncols..(row + 1) * ncols].to_vec();  // A[row][..] remains unchanged
+        let rows = self.values.chunks_mut(ncols).enumerate()
+            .filter(|&(i, _)| i != row)
+            .map(|(_, values)| values)
+            .collect();
+
+        for_each_row(rows, parallel, |values: &mut [T]| {
+            let nonzero = !values[col].is_zero();
+            for j in 0..ncols {
+                if j != col {  // A[..][col] remains unchanged
+
+                    //A[i,j] = (A[i,j] A[row,col] - A[i,col] A[row,j]) / det
+                    let mut tmp1 = values[j].mul_ref(&entry_row_col_abs);
+                    if nonzero {
+                        let tmp2 = pivot_row[j].mul_ref(&values[col]);
+                        tmp1 = if negpivot {
+                            tmp1.add_ref(&tmp2)
+                        } else {
+                            tmp1.sub_ref(&tmp2)
+                        };
+                    }
+                    values[j] = tmp1.div_exact(&cur_det);
+                }
+            }
+            if nonzero && !negpivot {
+                // row  i  has been dealt with, update  A[i][col] safely
+                values[col] = values[col].negated();
+            }
+        });
 
         self.set(row, col, cur_det);
     	if negpivot {
@@ -76,16 +180,98 @@ impl Tableau {
     	self.determinant = entry_row_col_abs; //by construction always positive
     }
 
+    /*
+     * same pivot step with a coefficient  row_dets[i]  per row instead
+     * of the common determinant.  No division by the old determinant:
+     * each row is made primitive by its gcd instead, which is never
+     * larger than the fraction-free row.  determinant  is still the
+     * fraction-free one,  |A[row][col]| * det / row_dets[row].
+     */
+    fn pivot_normalized(&mut self, row: usize, col: usize) {
+
+    	let (entry_row_col_abs, negpivot) = {
+            let entry_row_col = self.entry(row, col);
+            if entry_row_col.is_zero() {
+                panic!("Trying to pivot on a zero");
+            }
+            (entry_row_col.abs_value(), entry_row_col.is_negative())
+        };
+
+        let mut row_dets = self.row_dets.take().unwrap();
+        let row_det = row_dets[row].clone();
+        let ncols = self.ncols;
+        let parallel = self.is_parallel();
+        let pivot_row = self.values[row * ncols..(row + 1) * ncols].to_vec();
+        let rows = self.values.chunks_mut(ncols).zip(row_dets.iter_mut()).enumerate()
+            .filter(|&(i, _)| i != row)
+            .map(|(_, row_and_det)| row_and_det)
+            .collect();
+
+        for_each_row(rows, parallel, |(values, row_det_i): (&mut [T], &mut T)| {
+            let entry_i_col = values[col].clone();
+            let nonzero = !entry_i_col.is_zero();
+            for j in 0..ncols {
+                if j != col {
+                    let mut tmp1 = values[j].mul_ref(&entry_row_col_abs);
+                    if nonzero {
+                        let tmp2 = pivot_row[j].mul_ref(&entry_i_col);
+                        tmp1 = if negpivot {
+                            tmp1.add_ref(&tmp2)
+                        } else {
+                            tmp1.sub_ref(&tmp2)
+                        };
+                    }
+                    values[j] = tmp1;
+                }
+            }
+            // coefficient of the leaving variable, now cobasic in  col
+            let leaving = entry_i_col.mul_ref(&row_det);
+            values[col] = if negpivot { leaving } else { leaving.negated() };
+            *row_det_i = row_det_i.mul_ref(&entry_row_col_abs);
+        });
+
+        self.set(row, col, row_det.clone());
+    	if negpivot {
+    		self.negate_row(row);
+    	}
+        row_dets[row] = entry_row_col_abs.clone();
+
+        self.determinant = entry_row_col_abs.mul_ref(&self.determinant).div_exact(&row_det);
+
+        let rows = self.values.chunks_mut(ncols).zip(row_dets.iter_mut()).collect();
+        for_each_row(rows, parallel, |(values, row_det_i): (&mut [T], &mut T)| divide_by_gcd(values, row_det_i));
+        self.row_dets = Some(row_dets);
+    }
+
+    /*
+     * number of entries from which rows are updated on several
+     * threads,  PARALLEL_THRESHOLD  by default (parallel  feature only)
+     */
+    #[cfg(feature = "parallel")]
+    pub fn set_parallel_threshold(&mut self, entries: usize) {
+        self.parallel_threshold = entries;
+    }
+
+    #[cfg(feature = "parallel")]
+    fn is_parallel(&self) -> bool {
+        self.values.len() >= self.parallel_threshold
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn is_parallel(&self) -> bool {
+        false
+    }
+
     fn negate_row(&mut self, row: usize) {
     	for j in 0..self.ncols {
-    		let neg_entry = self.entry(row, j).neg();
+    		let neg_entry = self.entry(row, j).negated();
     		self.set(row, j, neg_entry);
     	}
     }
 
     pub fn negate_col(&mut self, col: usize) {
     	for i in 0..self.nrows {
-    		let neg_entry = self.entry(i, col).neg();
+    		let neg_entry = self.entry(i, col).negated();
     		self.set(i, col, neg_entry);
     	}
     }
@@ -93,12 +279,94 @@ impl Tableau {
     // sign of  A[a,testcol] / A[a,col] - A[b,testcol] / A[b,col]
     // (assumes only positive entries of col are considered)
     pub fn ratio_test(&self, rowa: usize, rowb: usize, cola: usize, colb: usize) -> Ordering {
-    	let a = self.entry(rowa, colb).mul(self.entry(rowb, cola));
-    	let b = self.entry(rowb, colb).mul(self.entry(rowa, cola));
+    	let a = self.entry(rowa, colb).mul_ref(self.entry(rowb, cola));
+    	let b = self.entry(rowb, colb).mul_ref(self.entry(rowa, cola));
     	a.cmp(&b)
     }
 }
 
+// rows of a pivot step are independent of each other
+#[cfg(feature = "parallel")]
+fn for_each_row<R: Send, F: Fn(R) + Send + Sync>(rows: Vec<R>, parallel: bool, update: F) {
+    if parallel {
+        rows.into_par_iter().for_each(update)
+    } else {
+        rows.into_iter().for_each(update)
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+fn for_each_row<R, F: Fn(R)>(rows: Vec<R>, _parallel: bool, update: F) {
+    rows.into_iter().for_each(update)
+}
+
+// divide a row and its basic coefficient by their gcd, keeping the coefficient positive
+fn divide_by_gcd<T: TableauInt>(values: &mut [T], row_det: &mut T) {
+    let mut gcd = row_det.abs_value();
+    for value in values.iter() {
+        if gcd.is_one() {
+            break;
+        }
+        gcd = gcd.gcd_ref(value);
+    }
+    if row_det.is_negative() {
+        gcd = gcd.negated();
+    } else if gcd.is_one() {
+        return;
+    }
+    for value in values.iter_mut() {
+        *value = value.div_exact(&gcd);
+    }
+    *row_det = row_det.div_exact(&gcd);
+}
+
+impl<T: TableauInt> TableauEngine for IntTableau<T> {
+
+    fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    fn set(&mut self, row: usize, col: usize, value: BigInt) {
+        IntTableau::set(self, row, col, T::from_bigint(value))
+    }
+
+    fn entry(&self, row: usize, col: usize) -> Cow<'_, BigInt> {
+        IntTableau::entry(self, row, col).to_bigint()
+    }
+
+    fn determinant(&self) -> Cow<'_, BigInt> {
+        self.determinant.to_bigint()
+    }
+
+    fn row_determinant(&self, row: usize) -> Cow<'_, BigInt> {
+        IntTableau::row_determinant(self, row).to_bigint()
+    }
+
+    fn pivot(&mut self, row: usize, col: usize) {
+        IntTableau::pivot(self, row, col)
+    }
+
+    fn negate_col(&mut self, col: usize) {
+        IntTableau::negate_col(self, col)
+    }
+
+    fn ratio_test(&self, rowa: usize, rowb: usize, cola: usize, colb: usize) -> Ordering {
+        IntTableau::ratio_test(self, rowa, rowb, cola, colb)
+    }
+
+    fn normalize_rows(&mut self) {
+        IntTableau::normalize_rows(self)
+    }
+
+    fn max_bits(&self) -> Option<u64> {
+        Some(IntTableau::max_bits(self))
+    }
+}
+
 #[test]
 fn set_and_get_works() {
 
@@ -147,6 +415,36 @@ fn pivoting_works() {
     assert_eq!(&BigInt::from_i32(10).unwrap(), a.entry(1, 1));
 }
 
+#[test]
+fn normalized_pivoting_works() {
+
+    let n = 2;
+	let mut a = Tableau::new(n);
+	let mut b = Tableau::new(n);
+	b.normalize_rows();
+	for i in 0..n {
+		for j in 0..n+2 {
+			let value = BigInt::from_usize(2*((i + 1) + j*10)).unwrap();
+			a.set(i, j, value.clone());
+			b.set(i, j, value);
+		}
+	}
+
+	a.pivot(0, 1);
+	b.pivot(0, 1);
+	a.pivot(1, 0);
+	b.pivot(1, 0);
+
+	// same basic solutions  rhs[i] / det,  with smaller entries
+	assert_eq!(a.determinant, b.determinant);
+	for i in 0..n {
+		for j in 0..n+2 {
+			assert_eq!(a.entry(i, j).mul(b.row_determinant(i)), b.entry(i, j).mul(a.row_determinant(i)));
+		}
+	}
+	assert!(b.max_bits() < a.max_bits());
+}
+
 #[test]
 fn negating_col_works() {
 
@@ -182,3 +480,66 @@ fn positive_values_ratio_test_works() {
     assert_eq!(Ordering::Greater, a.ratio_test(0, 1, 0, 1));
     assert_eq!(Ordering::Less, a.ratio_test(1, 0, 0, 1));
 }
+
+#[cfg(feature = "gmp")]
+#[test]
+fn gmp_pivoting_matches_bigint_pivoting() {
+
+	let n = 3;
+	let mut a = Tableau::new(n);
+	let mut b = GmpTableau::new(n);
+	for i in 0..n {
+		for j in 0..n+2 {
+			let value = BigInt::from_i64(((i * 7 + j * 13) % 11) as i64 - 5).unwrap();
+			TableauEngine::set(&mut a, i, j, value.clone());
+			TableauEngine::set(&mut b, i, j, value);
+		}
+	}
+
+	for &(row, col) in &[(0, 1), (1, 2), (2, 0)] {
+		a.pivot(row, col);
+		b.pivot(row, col);
+		for i in 0..n {
+			for j in 0..n+2 {
+				assert_eq!(TableauEngine::entry(&a, i, j), TableauEngine::entry(&b, i, j));
+			}
+		}
+		assert_eq!(TableauEngine::determinant(&a), TableauEngine::determinant(&b));
+	}
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_pivoting_matches_serial_pivoting() {
+
+	let n = 40;
+	for &normalized in &[false, true] {
+		let mut serial = Tableau::new(n);
+		let mut parallel = Tableau::new(n);
+		serial.set_parallel_threshold(usize::MAX);
+		parallel.set_parallel_threshold(0);
+		if normalized {
+			serial.normalize_rows();
+			parallel.normalize_rows();
+		}
+		for i in 0..n {
+			for j in 0..n+2 {
+				let value = BigInt::from_usize((i * 31 + j * 17) % 23 + 1).unwrap();
+				serial.set(i, j, value.clone());
+				parallel.set(i, j, value);
+			}
+		}
+
+		for &(row, col) in &[(0, 1), (5, 7), (17, 0), (39, 41)] {
+			serial.pivot(row, col);
+			parallel.pivot(row, col);
+			for i in 0..n {
+				assert_eq!(serial.row_determinant(i), parallel.row_determinant(i));
+				for j in 0..n+2 {
+					assert_eq!(serial.entry(i, j), parallel.entry(i, j));
+				}
+			}
+			assert_eq!(serial.determinant, parallel.determinant);
+		}
+	}
+}
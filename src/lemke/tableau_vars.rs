@@ -1,12 +1,13 @@
-use num::bigint::BigInt;
 use num::rational::{Ratio,BigRational};
 use num::traits::{One,Zero};
 
+use std::cmp::Ordering;
 use std::fmt::{Formatter,Debug,Error};
-use std::ops::Mul;
+use std::ops::{Div,Mul,Neg};
 
-use super::tableau::Tableau;
+use super::tableau::TableauEngine;
 
+#[derive(Clone)]
 pub struct TableauVariable {
     value: usize,
     n: usize,
@@ -61,12 +62,28 @@ impl PartialEq for TableauVariable {
     }
 }
 
+impl Eq for TableauVariable {}
+
+// VARS order  Z(0) .. Z(n) W(1) .. W(n), used for least-index rules
+impl PartialOrd for TableauVariable {
+    fn partial_cmp(&self, other: &TableauVariable) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TableauVariable {
+    fn cmp(&self, other: &TableauVariable) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
 impl Debug for TableauVariable {
     fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
         formatter.write_str(&self.to_string())
     }
 }
 
+#[derive(Clone)]
 pub struct TableauVariables {
     /*  v in VARS, v cobasic:  TABCOL(v) is v's tableau col */
 	/*  v  basic:  TABCOL(v) < 0,  TABCOL(v)+n   is v's row */
@@ -76,6 +93,9 @@ pub struct TableauVariables {
 	vars2rowcol: Vec<usize>,
 	rowcol2vars: Vec<usize>,
     pub n: usize,
+
+    // names of  Z(1)..Z(n)  for traces and errors, empty if not named
+    names: Vec<String>,
 }
 
 impl TableauVariables {
@@ -90,6 +110,7 @@ impl TableauVariables {
             n: n,
             vars2rowcol: vec![0; 2*n+1],
             rowcol2vars: vec![0; 2*n+1],
+            names: Vec::new(),
         };
 
         // w[1..n]
@@ -107,6 +128,42 @@ impl TableauVariables {
         vars
     }
 
+    /*
+     * name  Z(1)..Z(n),  e.g. by the game strategies they stand for.
+     * W(i)  is shown as the slack of  Z(i),  Z(0)  keeps its name.
+     */
+    pub fn set_names(&mut self, names: Vec<String>) {
+        if names.len() != self.n {
+            panic!("Expected {} variable names but got {}", self.n, names.len());
+        }
+        self.names = names;
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    // the variable written  z{i}  or  w{i}  (as by  to_string)
+    pub fn from_string(&self, var: &str) -> Option<TableauVariable> {
+        let idx: usize = var.get(1..)?.parse().ok()?;
+        match var.chars().next() {
+            Some('z') if idx <= self.n => Some(self.z(idx)),
+            Some('w') if idx >= 1 && idx <= self.n => Some(self.w(idx)),
+            _ => None,
+        }
+    }
+
+    // the name of  var  if named, otherwise  z{i}  or  w{i}
+    pub fn name(&self, var: &TableauVariable) -> String {
+        if self.names.is_empty() || var.is_z0() {
+            var.to_string()
+        } else if var.is_z() {
+            self.names[var.value - 1].clone()
+        } else {
+            format!("slack({})", self.names[var.value - self.n - 1])
+        }
+    }
+
     // TODO: check bounds with panic?
     pub fn z(&self, idx: usize) -> TableauVariable {
         TableauVariable{ value: idx, n: self.n }
@@ -140,7 +197,7 @@ impl TableauVariables {
         self.n + 1
     }
 
-    pub fn negate_rhs(&self, tableau: &mut Tableau) {
+    pub fn negate_rhs(&self, tableau: &mut dyn TableauEngine) {
         tableau.negate_col(self.rhs_col())
     }
 
@@ -165,14 +222,14 @@ impl TableauVariables {
      * @param leave (r) VAR defining row of pivot element
      * @param enter (s) VAR defining col of pivot element
      */
-     pub fn pivot(&mut self, tableau: &mut Tableau, leave: &TableauVariable, enter: &TableauVariable) {
+     pub fn pivot(&mut self, tableau: &mut dyn TableauEngine, leave: &TableauVariable, enter: &TableauVariable) {
 
      	if !self.is_basic(leave) {
-     		panic!("{} is not in the basis", leave.to_string());
+     		panic!("{} is not in the basis", self.name(leave));
      	}
 
      	if self.is_basic(enter) {
-     		panic!("{} is already in the basis", enter.to_string());
+     		panic!("{} is already in the basis", self.name(enter));
      	}
 
      	let (row, col) = self.swap(enter, leave);  // update tableau variables
@@ -184,7 +241,7 @@ impl TableauVariables {
       * current basic solution turned into  solz [0..n-1]
       * note that Z(1)..Z(n)  become indices  0..n-1
       */
-     pub fn solution(&self, tableau: &Tableau, scale_factors: &Vec<BigInt>) -> Vec<BigRational> {
+     pub fn solution(&self, tableau: &dyn TableauEngine, scale_factors: &Vec<BigRational>) -> Vec<BigRational> {
 
      	let mut z = Vec::with_capacity(self.n);
      	for i in 1..self.n + 1 {
@@ -193,14 +250,79 @@ impl TableauVariables {
      	z
      }
 
+     /*
+      * unbounded ray when  enter  can increase without bound
+      * the cobasic  enter  moves by one, each basic variable by
+      * minus its entry in the entering col over its row determinant,
+      * scaled like in  result
+      * returns the  z  part  Z(1)..Z(n)  and the  z0  part
+      */
+     pub fn ray(&self, tableau: &dyn TableauEngine, scale_factors: &[BigRational], enter: &TableauVariable) -> (Vec<BigRational>, BigRational) {
+
+        let col = self.to_col(enter);
+        let direction = |var: &TableauVariable| -> BigRational {
+            let value = if self.is_basic(var) {
+                let row = self.to_row(var);
+                Ratio::new(tableau.entry(row, col).into_owned().neg(), tableau.row_determinant(row).into_owned())
+            } else if var == enter {
+                BigRational::one()
+            } else {
+                BigRational::zero()
+            };
+            if var.is_z() {
+                value.mul(&scale_factors[var.value])
+            } else {
+                value
+            }
+        };
+
+        let z = (1..self.n + 1).map(|i| direction(&self.z(i))).collect();
+        (z, direction(&self.z(0)))
+     }
+
+     /*
+      * the tableau as exact rationals in the unscaled variables:
+      * row  i  reads  x_B(i) + sum_j T[i][j] x_N(j) = T[i][RHS]
+      * with  x_N(j) = from_col(j).  Each entry is multiplied by the
+      * scale factor of the basic variable and divided by the row
+      * determinant and the scale factor of its column, as in  result
+      */
+     pub fn rationals(&self, tableau: &dyn TableauEngine, scale_factors: &[BigRational]) -> Vec<Vec<BigRational>> {
+
+        let one = BigRational::one();
+        let scale_factor = |var: &TableauVariable| if var.is_z() { &scale_factors[var.value] } else { &one };
+
+        (0..self.n).map(|row| {
+            let row_scale = scale_factor(&self.from_row(row)).div(&Ratio::from_integer(tableau.row_determinant(row).into_owned()));
+            (0..self.n + 2).map(|col| {
+                let col_scale = if col == self.rhs_col() {
+                    &scale_factors[col]
+                } else {
+                    scale_factor(&self.from_col(col))
+                };
+                Ratio::from_integer(tableau.entry(row, col).into_owned()).mul(&row_scale).div(col_scale)
+            }).collect()
+        }).collect()
+     }
+
+     // header of tableau column  col:  its cobasic variable or RHS
+     pub fn col_name(&self, col: usize) -> String {
+        if col == self.rhs_col() {
+            "RHS".to_string()
+        } else {
+            self.name(&self.from_col(col))
+        }
+     }
+
      /*
       * Z(i):  scfa[i]*rhs[row] / (scfa[RHS]*det)
       * W(i):  rhs[row] / (scfa[RHS]*det)
+      * with  det  the row determinant if the tableau rows are normalized
       */
-    fn result(&self, tableau: &Tableau, scale_factors: &Vec<BigInt>, var: &TableauVariable) -> BigRational {
+    pub fn result(&self, tableau: &dyn TableauEngine, scale_factors: &Vec<BigRational>, var: &TableauVariable) -> BigRational {
         if self.is_basic(var) {
 
-            let one = BigInt::one();
+            let one = BigRational::one();
             let scale_factor = if var.is_z() {
                 &scale_factors[var.value]
             } else {
@@ -209,10 +331,10 @@ impl TableauVariables {
 
             let row = self.to_row(var);
             let col = self.rhs_col();
-            let numer = scale_factor.mul(tableau.entry(row, col));
-            let denom = (&tableau.determinant).mul(&scale_factors[col]);
+            let numer = scale_factor.mul(&Ratio::from_integer(tableau.entry(row, col).into_owned()));
+            let denom = scale_factors[col].clone().mul(&Ratio::from_integer(tableau.row_determinant(row).into_owned()));
 
-            Ratio::new(numer, denom)
+            numer.div(denom)
         } else {
             BigRational::zero()
         }
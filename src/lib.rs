@@ -1,5 +1,10 @@
 extern crate num;
-extern crate time;
+extern crate rand;
+extern crate rand_chacha;
+#[cfg(feature = "gmp")] extern crate rug;
+#[cfg(feature = "parallel")] extern crate rayon;
+#[cfg(feature = "serde")] extern crate serde;
+#[cfg(all(test, feature = "serde"))] extern crate serde_json;
 
-mod lemke;
-mod game;
+pub mod lemke;
+pub mod game;